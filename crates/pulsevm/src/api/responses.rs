@@ -1,6 +1,7 @@
 use pulsevm_core::{block::BlockTimestamp, id::Id, name::Name, utils::Base64Bytes};
 use pulsevm_crypto::Digest;
 use serde::Serialize;
+use serde_json::Value;
 
 #[derive(Serialize, Clone, Default)]
 pub struct GetInfoResponse {
@@ -39,6 +40,11 @@ pub struct GetCodeHashResponse {
     pub code_hash: Id,
 }
 
+#[derive(Serialize, Clone, Default)]
+pub struct DbFlushResponse {
+    pub flushed: bool,
+}
+
 #[derive(Serialize, Clone, Default)]
 pub struct GetRawABIResponse {
     pub account_name: Name,
@@ -46,3 +52,31 @@ pub struct GetRawABIResponse {
     pub abi_hash: Digest,
     pub abi: Base64Bytes,
 }
+
+#[derive(Serialize, Clone, Default)]
+pub struct AbiJsonToBinResponse {
+    pub binargs: String,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct AbiBinToJsonResponse {
+    pub args: Value,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct SetLogLevelResponse {
+    pub level: String,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct GetResourceUsageResponse {
+    pub account_name: Name,
+    pub ram_usage: i64,
+    pub ram_quota: i64,
+    pub net_used: i64,
+    pub net_available: i64,
+    pub net_max: i64,
+    pub cpu_used: i64,
+    pub cpu_available: i64,
+    pub cpu_max: i64,
+}
@@ -73,9 +73,43 @@ impl Gossipable {
     }
 }
 
+/// A peer's self-reported client version, as carried by Avalanche's
+/// `ConnectedRequest`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PeerVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl PeerVersion {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl std::fmt::Display for PeerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The oldest peer version gossip messages may be sent to. Peers below this
+/// version are assumed to use an incompatible wire format.
+pub const MIN_COMPATIBLE_PEER_VERSION: PeerVersion = PeerVersion {
+    major: 1,
+    minor: 0,
+    patch: 0,
+};
+
 pub struct ConnectedNode {
     #[allow(dead_code)]
     pub id: NodeId,
+    pub version: PeerVersion,
 }
 
 pub struct NetworkManager {
@@ -91,9 +125,34 @@ impl NetworkManager {
         }
     }
 
-    pub fn connected(&mut self, node_id: NodeId) {
+    pub fn connected(&mut self, node_id: NodeId, version: PeerVersion) {
+        self.connected_nodes.insert(
+            node_id,
+            ConnectedNode {
+                id: node_id,
+                version,
+            },
+        );
+    }
+
+    /// Returns the version a connected peer reported, if any.
+    pub fn peer_version(&self, node_id: &NodeId) -> Option<PeerVersion> {
+        self.connected_nodes.get(node_id).map(|n| n.version)
+    }
+
+    /// Lists the ids of all currently connected peers.
+    pub fn connected_peers(&self) -> Vec<NodeId> {
+        self.connected_nodes.keys().copied().collect()
+    }
+
+    /// Lists the ids of connected peers whose version is at least
+    /// `min_version`.
+    fn compatible_peers(&self, min_version: PeerVersion) -> Vec<NodeId> {
         self.connected_nodes
-            .insert(node_id, ConnectedNode { id: node_id });
+            .values()
+            .filter(|node| node.version >= min_version)
+            .map(|node| node.id)
+            .collect()
     }
 
     pub fn disconnected(&mut self, node_id: NodeId) {
@@ -120,9 +179,21 @@ impl NetworkManager {
             ChainError::NetworkError(format!("failed to serialize gossipable: {}", e))
         })?;
 
+        // Skip peers we know are running an incompatible version; if we
+        // haven't tracked any peers yet, fall back to letting the engine
+        // sample so gossip still works before the first Connected callback.
+        let node_ids: Vec<Vec<u8>> = if self.connected_nodes.is_empty() {
+            vec![]
+        } else {
+            self.compatible_peers(MIN_COMPATIBLE_PEER_VERSION)
+                .into_iter()
+                .map(Into::into)
+                .collect()
+        };
+
         let result = client
             .send_app_gossip(Request::new(SendAppGossipMsg {
-                node_ids: vec![], // don't hand-pick; let the engine sample
+                node_ids,
                 validators: 3,
                 non_validators: 0,
                 peers: 2,
@@ -140,3 +211,36 @@ impl NetworkManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connected_tracks_peer_version() {
+        let mut manager = NetworkManager::new();
+        let compatible = NodeId([1u8; 20]);
+        let incompatible = NodeId([2u8; 20]);
+
+        manager.connected(compatible, PeerVersion::new(1, 2, 0));
+        manager.connected(incompatible, PeerVersion::new(0, 9, 0));
+
+        assert_eq!(
+            manager.peer_version(&compatible),
+            Some(PeerVersion::new(1, 2, 0))
+        );
+        assert_eq!(
+            manager.peer_version(&incompatible),
+            Some(PeerVersion::new(0, 9, 0))
+        );
+
+        let mut listed = manager.connected_peers();
+        listed.sort_by_key(|id| id.0);
+        assert_eq!(listed, vec![compatible, incompatible]);
+
+        assert_eq!(
+            manager.compatible_peers(MIN_COMPATIBLE_PEER_VERSION),
+            vec![compatible]
+        );
+    }
+}
@@ -2,7 +2,8 @@ use std::{collections::BTreeSet, str::FromStr, sync::Arc};
 
 use jsonrpsee::{proc_macros::rpc, types::ErrorObjectOwned};
 use pulsevm_core::{
-    abi::AbiDefinition,
+    Database,
+    abi::{AbiDefinition, AbiSerializer},
     authorization_manager::AuthorizationManager,
     block::SignedBlock,
     controller::Controller,
@@ -10,8 +11,10 @@ use pulsevm_core::{
     id::Id,
     mempool::Mempool,
     name::Name,
+    producer_schedule::ProducerSchedule,
+    resource_limits::ResourceLimitsManager,
     time::{TimePoint, seconds},
-    transaction::{PackedTransaction, Transaction, TransactionCompression},
+    transaction::{PackedTransaction, Transaction, TransactionCompression, TransactionTrace},
     utils::{Base64Bytes, I32Flex},
 };
 use pulsevm_crypto::{Bytes, Digest};
@@ -21,7 +24,11 @@ use tokio::sync::RwLock;
 use tonic::async_trait;
 
 use crate::{
-    api::{GetCodeHashResponse, GetInfoResponse, GetRawABIResponse, IssueTxResponse},
+    api::{
+        AbiBinToJsonResponse, AbiJsonToBinResponse, DbFlushResponse, GetCodeHashResponse,
+        GetInfoResponse, GetRawABIResponse, GetResourceUsageResponse, IssueTxResponse,
+        SetLogLevelResponse,
+    },
     chain::{GossipType, Gossipable, NetworkManager},
 };
 
@@ -36,6 +43,15 @@ pub trait Rpc {
         packed_trx: Bytes,
     ) -> Result<IssueTxResponse, ErrorObjectOwned>;
 
+    #[method(name = "pulsevm.pushRoTransaction")]
+    async fn push_ro_transaction(
+        &self,
+        signatures: BTreeSet<Signature>,
+        compression: TransactionCompression,
+        packed_context_free_data: Bytes,
+        packed_trx: Bytes,
+    ) -> Result<TransactionTrace, ErrorObjectOwned>;
+
     #[method(name = "pulsevm.getABI")]
     async fn get_abi(&self, account_name: Name) -> Result<AbiDefinition, ErrorObjectOwned>;
 
@@ -55,6 +71,14 @@ pub trait Rpc {
         account_name: Name,
     ) -> Result<GetCodeHashResponse, ErrorObjectOwned>;
 
+    /// Flushes the underlying chainbase database to disk. Intended for
+    /// operators maintaining long-running test networks, not for public
+    /// callers -- deploy this node's RPC endpoint behind a network
+    /// boundary that keeps untrusted clients from reaching it, the same
+    /// way any other node-maintenance operation would be.
+    #[method(name = "pulsevm.dbFlush")]
+    async fn db_flush(&self) -> Result<DbFlushResponse, ErrorObjectOwned>;
+
     #[method(name = "pulsevm.getCurrencyBalance")]
     async fn get_currency_balance(
         &self,
@@ -87,6 +111,25 @@ pub trait Rpc {
         candidate_keys: BTreeSet<PublicKey>,
     ) -> Result<BTreeSet<PublicKey>, ErrorObjectOwned>;
 
+    #[method(name = "pulsevm.getTransaction")]
+    async fn get_transaction(&self, id: Id) -> Result<Value, ErrorObjectOwned>;
+
+    #[method(name = "pulsevm.abiJsonToBin")]
+    async fn abi_json_to_bin(
+        &self,
+        code: Name,
+        action: Name,
+        args: Value,
+    ) -> Result<AbiJsonToBinResponse, ErrorObjectOwned>;
+
+    #[method(name = "pulsevm.abiBinToJson")]
+    async fn abi_bin_to_json(
+        &self,
+        code: Name,
+        action: Name,
+        binargs: Bytes,
+    ) -> Result<AbiBinToJsonResponse, ErrorObjectOwned>;
+
     #[method(name = "pulsevm.getTableByScope")]
     async fn get_table_by_scope(
         &self,
@@ -115,6 +158,58 @@ pub trait Rpc {
         reverse: Option<bool>,
         show_payer: Option<bool>,
     ) -> Result<Value, ErrorObjectOwned>;
+
+    #[method(name = "pulsevm.getProducers")]
+    async fn get_producers(&self) -> Result<ProducerSchedule, ErrorObjectOwned>;
+
+    #[method(name = "pulsevm.getKvTableRows")]
+    async fn get_kv_table_rows(
+        &self,
+        code: Name,
+        scope: String,
+        table: Name,
+        limit: Option<I32Flex>,
+    ) -> Result<Value, ErrorObjectOwned>;
+
+    /// A focused view of an account's current-window resource usage --
+    /// RAM bytes used/quota, and CPU/NET used/available/max for the
+    /// present rate-limiting window -- assembled from the same resource
+    /// usage accumulators `pulsevm.getAccount` draws on. Useful when a
+    /// caller only cares about resource headroom and doesn't want to
+    /// parse the full `getAccount` payload.
+    #[method(name = "pulsevm.getResourceUsage")]
+    async fn get_resource_usage(
+        &self,
+        account_name: Name,
+    ) -> Result<GetResourceUsageResponse, ErrorObjectOwned>;
+
+    /// Changes the running node's log level without a restart. Accepts
+    /// the same names as the `PULSEVM_LOG_LEVEL` env var that sets the
+    /// initial level at startup (off/critical/error/warn/info/debug/trace,
+    /// case-insensitive). Intended for operators diagnosing an issue, not
+    /// for public callers -- deploy this node's RPC endpoint behind a
+    /// network boundary the same way `pulsevm.dbFlush` is.
+    #[method(name = "pulsevm.setLogLevel")]
+    async fn set_log_level(&self, level: String) -> Result<SetLogLevelResponse, ErrorObjectOwned>;
+}
+
+/// Parses a log level name into the `LevelFilter` that `spdlog` would
+/// enable for it: `"off"`/`"all"` map to their matching filter variants,
+/// and anything else is tried as a [`spdlog::Level`] name and turned into
+/// "this level and anything more severe". Shared between the
+/// `PULSEVM_LOG_LEVEL` startup env var and `pulsevm.setLogLevel` so both
+/// accept exactly the same names.
+pub fn parse_log_level_filter(level: &str) -> Option<spdlog::LevelFilter> {
+    if level.eq_ignore_ascii_case("off") {
+        Some(spdlog::LevelFilter::Off)
+    } else if level.eq_ignore_ascii_case("all") {
+        Some(spdlog::LevelFilter::All)
+    } else {
+        level
+            .parse::<spdlog::Level>()
+            .ok()
+            .map(spdlog::LevelFilter::MoreSevereEqual)
+    }
 }
 
 #[derive(Clone)]
@@ -150,6 +245,42 @@ impl RpcService {
 
         Ok(resp)
     }
+
+    /// Same as `handle_api_request`, but re-serializes the response in
+    /// canonical form: object keys sorted, arrays left in their original
+    /// order. Clients that hash responses (e.g. to dedupe or cache them)
+    /// need this so that two logically identical responses always produce
+    /// byte-identical JSON, regardless of the iteration order of any map
+    /// the response happened to be built from along the way. Exposed to
+    /// HTTP callers via the `X-Canonical-Json` request header.
+    pub async fn handle_api_request_canonical(
+        &self,
+        request_body: &str,
+    ) -> Result<String, serde_json::Error> {
+        let resp = self.handle_api_request(request_body).await?;
+        canonicalize_json(&resp)
+    }
+
+    fn abi_serializer_for(
+        &self,
+        db: &Database,
+        code: Name,
+    ) -> Result<AbiSerializer, ErrorObjectOwned> {
+        let code_account = db.get_account(code.as_u64())?;
+        let abi_bytes = code_account.get_abi();
+        if abi_bytes.as_slice().is_empty() {
+            return Err(ErrorObjectOwned::owned(
+                400,
+                "abi_error",
+                Some(format!("account '{}' has no ABI set", code)),
+            ));
+        }
+
+        let abi = AbiDefinition::read(abi_bytes.as_slice(), &mut 0).map_err(|e| {
+            ErrorObjectOwned::owned(400, "abi_error", Some(format!("failed to read ABI: {}", e)))
+        })?;
+        AbiSerializer::from_abi(abi).map_err(ErrorObjectOwned::from)
+    }
 }
 
 #[async_trait]
@@ -164,6 +295,13 @@ impl RpcServer for RpcService {
         Ok(abi)
     }
 
+    async fn get_producers(&self) -> Result<ProducerSchedule, ErrorObjectOwned> {
+        let controller = self.controller.read().await;
+        let db = controller.database();
+        let schedule = Controller::get_producer_schedule(&db)?;
+        Ok(schedule)
+    }
+
     async fn get_account(
         &self,
         name: Name,
@@ -221,6 +359,61 @@ impl RpcServer for RpcService {
         })
     }
 
+    async fn get_resource_usage(
+        &self,
+        account_name: Name,
+    ) -> Result<GetResourceUsageResponse, ErrorObjectOwned> {
+        let controller = self.controller.read().await;
+        let db = controller.database();
+
+        let ram_usage = db.get_account_ram_usage(account_name.as_u64())?;
+        let mut ram_quota = 0i64;
+        let mut net_weight = 0i64;
+        let mut cpu_weight = 0i64;
+        db.get_account_limits(
+            account_name.as_u64(),
+            &mut ram_quota,
+            &mut net_weight,
+            &mut cpu_weight,
+        )?;
+
+        let (net_used, net_available, net_max) =
+            ResourceLimitsManager::get_account_net_usage(&db, &account_name, None)?;
+        let (cpu_used, cpu_available, cpu_max) =
+            ResourceLimitsManager::get_account_cpu_usage(&db, &account_name, None)?;
+
+        Ok(GetResourceUsageResponse {
+            account_name,
+            ram_usage,
+            ram_quota,
+            net_used,
+            net_available,
+            net_max,
+            cpu_used,
+            cpu_available,
+            cpu_max,
+        })
+    }
+
+    async fn db_flush(&self) -> Result<DbFlushResponse, ErrorObjectOwned> {
+        let controller = self.controller.read().await;
+        let mut db = controller.database();
+        db.flush()?;
+        Ok(DbFlushResponse { flushed: true })
+    }
+
+    async fn set_log_level(&self, level: String) -> Result<SetLogLevelResponse, ErrorObjectOwned> {
+        let filter = parse_log_level_filter(&level).ok_or_else(|| {
+            ErrorObjectOwned::owned(
+                400,
+                "invalid_log_level",
+                Some(format!("unrecognized log level: {}", level)),
+            )
+        })?;
+        spdlog::default_logger().set_level_filter(filter);
+        Ok(SetLogLevelResponse { level })
+    }
+
     async fn get_currency_balance(
         &self,
         code: Name,
@@ -276,14 +469,20 @@ impl RpcServer for RpcService {
         let head_block = controller.last_accepted_block();
         let db = controller.database();
         let head_block_id = head_block.id()?;
+        let last_irreversible_block_num = controller.last_irreversible_block();
+        let last_irreversible_block_id = controller
+            .get_block_by_height(last_irreversible_block_num)?
+            .map(|block| block.id())
+            .transpose()?
+            .unwrap_or(head_block_id);
 
         Ok(GetInfoResponse {
             server_version: "d133c641".to_owned(),
             server_time: TimePoint::now().into(),
             chain_id: controller.chain_id().clone(),
             head_block_num: head_block.block_num(),
-            last_irreversible_block_num: head_block.block_num(),
-            last_irreversible_block_id: head_block_id,
+            last_irreversible_block_num,
+            last_irreversible_block_id,
             head_block_id: head_block_id,
             head_block_time: head_block.timestamp().clone(),
             head_block_producer: head_block.signed_block_header.header.producer,
@@ -343,7 +542,9 @@ impl RpcServer for RpcService {
                     ));
                 }
             }
-        } else if let Ok(id) = Id::from_str(block_num_or_id.as_str()) {
+        } else if let Ok(id) = Id::from_str(block_num_or_id.as_str())
+            .or_else(|_| Id::from_cb58(block_num_or_id.as_str()))
+        {
             let block = controller.get_block(id)?;
 
             match block {
@@ -407,6 +608,29 @@ impl RpcServer for RpcService {
         })
     }
 
+    async fn push_ro_transaction(
+        &self,
+        signatures: BTreeSet<Signature>,
+        compression: TransactionCompression,
+        packed_context_free_data: Bytes,
+        packed_trx: Bytes,
+    ) -> Result<TransactionTrace, ErrorObjectOwned> {
+        let packed_trx = PackedTransaction::new(
+            signatures,
+            compression,
+            packed_context_free_data,
+            packed_trx,
+        )?;
+
+        // Simulate the transaction and roll it back; never touches the
+        // mempool or gets gossiped, unlike `issue_tx`.
+        let mut controller = self.controller.write().await;
+        let pending_block_timestamp = TimePoint::now().into();
+        let trace = controller.dry_run(&packed_trx, &pending_block_timestamp)?;
+
+        Ok(trace)
+    }
+
     async fn get_required_keys(
         &self,
         trx: Transaction,
@@ -425,6 +649,116 @@ impl RpcServer for RpcService {
         Ok(required_keys)
     }
 
+    async fn get_transaction(&self, id: Id) -> Result<Value, ErrorObjectOwned> {
+        let mut controller = self.controller.write().await;
+        let trace = controller
+            .get_transaction_trace(&id)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorObjectOwned::owned(
+                    404,
+                    "transaction_not_found",
+                    Some(format!(
+                        "transaction '{}' was not found in the trace cache",
+                        id
+                    )),
+                )
+            })?;
+        let db = controller.database();
+
+        let mut trace_json = serde_json::to_value(&trace).map_err(|e| {
+            ErrorObjectOwned::owned(500, "serialization_error", Some(format!("{}", e)))
+        })?;
+
+        // Best-effort: decode each action's data against its contract's ABI
+        // and attach it alongside the raw hex `data`, same as
+        // `abi_bin_to_json`. Left out entirely when the contract has no ABI
+        // or the action isn't declared in it.
+        if let Some(json_action_traces) = trace_json
+            .get_mut("action_traces")
+            .and_then(Value::as_array_mut)
+        {
+            for (action_trace, json_action_trace) in
+                trace.action_traces().iter().zip(json_action_traces)
+            {
+                let act = action_trace.action();
+                let Ok(serializer) = self.abi_serializer_for(&db, *act.account()) else {
+                    continue;
+                };
+                let Some(action_type) = serializer.get_action_type(act.name()) else {
+                    continue;
+                };
+                let Ok(decoded) = serializer.binary_to_variant(action_type, &act.data(), &mut 0)
+                else {
+                    continue;
+                };
+
+                if let Some(json_act) = json_action_trace.get_mut("act") {
+                    json_act["data_decoded"] = decoded;
+                }
+            }
+        }
+
+        Ok(trace_json)
+    }
+
+    async fn abi_json_to_bin(
+        &self,
+        code: Name,
+        action: Name,
+        args: Value,
+    ) -> Result<AbiJsonToBinResponse, ErrorObjectOwned> {
+        let controller = self.controller.read().await;
+        let db = controller.database();
+        let serializer = self.abi_serializer_for(&db, code)?;
+        let action_type = serializer.get_action_type(&action).ok_or_else(|| {
+            ErrorObjectOwned::owned(
+                400,
+                "action_not_found",
+                Some(format!(
+                    "action '{}' not found in the ABI for '{}'",
+                    action, code
+                )),
+            )
+        })?;
+
+        let mut binargs = Vec::new();
+        serializer
+            .variant_to_binary(action_type, &args, &mut binargs)
+            .map_err(ErrorObjectOwned::from)?;
+
+        Ok(AbiJsonToBinResponse {
+            binargs: hex::encode(binargs),
+        })
+    }
+
+    async fn abi_bin_to_json(
+        &self,
+        code: Name,
+        action: Name,
+        binargs: Bytes,
+    ) -> Result<AbiBinToJsonResponse, ErrorObjectOwned> {
+        let controller = self.controller.read().await;
+        let db = controller.database();
+        let serializer = self.abi_serializer_for(&db, code)?;
+        let action_type = serializer.get_action_type(&action).ok_or_else(|| {
+            ErrorObjectOwned::owned(
+                400,
+                "action_not_found",
+                Some(format!(
+                    "action '{}' not found in the ABI for '{}'",
+                    action, code
+                )),
+            )
+        })?;
+
+        let args = serializer
+            .binary_to_variant(action_type, binargs.as_ref(), &mut 0)
+            .map_err(ErrorObjectOwned::from)?;
+
+        Ok(AbiBinToJsonResponse { args })
+    }
+
     async fn get_table_by_scope(
         &self,
         code: Name,
@@ -492,4 +826,84 @@ impl RpcServer for RpcService {
 
         Ok(rows)
     }
+
+    async fn get_kv_table_rows(
+        &self,
+        code: Name,
+        scope: String,
+        table: Name,
+        limit: Option<I32Flex>,
+    ) -> Result<Value, ErrorObjectOwned> {
+        let controller = self.controller.read().await;
+        let db = controller.database();
+        let response = db.get_kv_table_rows(
+            code.as_u64(),
+            &scope,
+            table.as_u64(),
+            limit.unwrap_or(I32Flex(10)).0 as u32,
+        )?;
+
+        let rows: Value = serde_json::from_str(&response).map_err(|e| {
+            ErrorObjectOwned::owned(500, "serialization_error", Some(format!("{}", e)))
+        })?;
+
+        Ok(rows)
+    }
+}
+
+/// Re-serializes a JSON-RPC response string with object keys sorted, so
+/// that two calls returning the same logical data always produce the same
+/// bytes. `serde_json::Value` already keeps object keys in a `BTreeMap`
+/// (this crate does not enable the `preserve_order` feature), so a round
+/// trip through `Value` is enough to get a canonical, sorted-key encoding.
+fn canonicalize_json(response: &str) -> Result<String, serde_json::Error> {
+    let value: Value = serde_json::from_str(response)?;
+    serde_json::to_string(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalize_json_is_stable_regardless_of_source_key_order() {
+        let first = r#"{"b":2,"a":1,"c":{"z":3,"y":4}}"#;
+        let second = r#"{"a":1,"c":{"y":4,"z":3},"b":2}"#;
+
+        let canonical_first = canonicalize_json(first).unwrap();
+        let canonical_second = canonicalize_json(second).unwrap();
+
+        assert_eq!(canonical_first, canonical_second);
+        assert_eq!(canonical_first, r#"{"a":1,"b":2,"c":{"y":4,"z":3}}"#);
+    }
+
+    #[test]
+    fn parse_log_level_filter_accepts_off_all_and_known_level_names() {
+        assert_eq!(
+            parse_log_level_filter("off"),
+            Some(spdlog::LevelFilter::Off)
+        );
+        assert_eq!(
+            parse_log_level_filter("ALL"),
+            Some(spdlog::LevelFilter::All)
+        );
+        assert_eq!(
+            parse_log_level_filter("Debug"),
+            Some(spdlog::LevelFilter::MoreSevereEqual(spdlog::Level::Debug))
+        );
+        assert_eq!(parse_log_level_filter("not-a-level"), None);
+    }
+
+    #[test]
+    fn set_log_level_changes_what_the_default_logger_will_actually_log() {
+        let original_filter = spdlog::default_logger().level_filter();
+
+        let filter = parse_log_level_filter("warn").unwrap();
+        spdlog::default_logger().set_level_filter(filter);
+
+        assert!(!spdlog::default_logger().should_log(spdlog::Level::Info));
+        assert!(spdlog::default_logger().should_log(spdlog::Level::Warn));
+
+        spdlog::default_logger().set_level_filter(original_filter);
+    }
 }
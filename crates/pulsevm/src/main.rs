@@ -14,6 +14,7 @@ use pulsevm_grpc::{
         self, Element,
         http_server::{Http, HttpServer},
     },
+    io::prometheus::client,
     vm::{
         self, Handler, ParseBlockResponse,
         runtime::{InitializeRequest, runtime_client::RuntimeClient},
@@ -36,18 +37,21 @@ use tokio_util::sync::CancellationToken;
 use tonic::transport::server::TcpIncoming;
 use tonic::{Request, Response, Status, transport::Server};
 
-use crate::{
-    chain::{BlockTimer, GossipType, Gossipable},
-    state_history::StateHistoryServer,
-};
+use crate::chain::{BlockTimer, GossipType, Gossipable};
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
 async fn main() {
-    // Initialize logging
-    spdlog::default_logger().set_level_filter(spdlog::LevelFilter::All);
+    // Initialize logging. `PULSEVM_LOG_LEVEL` accepts the same level names
+    // as `pulsevm.setLogLevel` below (off/critical/error/warn/info/debug/
+    // trace, case-insensitive), so operators can turn on debug logging to
+    // diagnose an issue without restarting the node.
+    let level_filter = std::env::var("PULSEVM_LOG_LEVEL")
+        .ok()
+        .and_then(|level| chain::parse_log_level_filter(&level))
+        .unwrap_or(spdlog::LevelFilter::MoreSevereEqual(spdlog::Level::Info));
+    spdlog::default_logger().set_level_filter(level_filter);
 
     let cancel = CancellationToken::new();
-    let cancel_ws = cancel.clone();
     let cancel_runtime = cancel.clone();
     let avalanche_addr = std::env::var("AVALANCHE_VM_RUNTIME_ENGINE_ADDR").unwrap();
     let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind to address");
@@ -59,8 +63,10 @@ async fn main() {
         TokioTcpListener::from_std(listener).expect("failed to convert to tokio listener");
     let incoming = TcpIncoming::from_listener(tokio_listener, true, None)
         .expect("failed to create incoming listener");
-    // Main VM instance
-    let vm = VirtualMachine::new(addr).unwrap();
+    // Main VM instance. Its SHIP server (if `PULSEVM_SHIP_ENABLED` is set) is
+    // started from `initialize` below and shares `cancel`, so it shuts down
+    // and drains its sessions the moment the VM is told to shut down.
+    let vm = VirtualMachine::new(addr, cancel.clone()).unwrap();
 
     let runtime_vm = vm.clone();
     let runtime_handle = tokio::spawn(async move {
@@ -82,20 +88,9 @@ async fn main() {
         .await
         .expect("failed to initialize runtime engine");
 
-    let state_history_service = StateHistoryServer::new(vm.clone());
-    let ws_bind = std::env::var("WS_BIND").unwrap_or_else(|_| "0.0.0.0:9090".into());
-    let ws_handle = tokio::spawn(async move {
-        if let Err(e) = state_history_service
-            .run_ws_server(&ws_bind, cancel_ws)
-            .await
-        {
-            spdlog::error!("WS server error: {:?}", e);
-        }
-    });
-
     // Keep listening
     let _ = runtime_handle.await;
-    let _ = ws_handle.await;
+    vm.join_ship_server().await;
 
     // Gracefully shutdown
     info!("shutting down...");
@@ -155,10 +150,15 @@ pub struct VirtualMachine {
     rpc_service: chain::RpcService,
     block_timer: Arc<RwLock<BlockTimer>>,
     ready_to_terminate: Arc<AtomicBool>,
+    cancel: CancellationToken,
+    ship_server: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
 }
 
 impl VirtualMachine {
-    pub fn new(server_addr: SocketAddr) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(
+        server_addr: SocketAddr,
+        cancel: CancellationToken,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let controller = Arc::new(RwLock::new(Controller::new()));
         let mempool = Arc::new(RwLock::new(Mempool::new()));
         let network_manager = Arc::new(RwLock::new(chain::NetworkManager::new()));
@@ -174,8 +174,81 @@ impl VirtualMachine {
             rpc_service: rpc_service,
             block_timer,
             ready_to_terminate: Arc::new(AtomicBool::new(false)),
+            cancel,
+            ship_server: Arc::new(RwLock::new(None)),
         })
     }
+
+    /// Starts the SHIP (state-history) websocket server in the background if
+    /// `PULSEVM_SHIP_ENABLED` says to, otherwise does nothing. Idempotent, so
+    /// it's safe to call every time `initialize` runs. The server shares
+    /// this VM's [`CancellationToken`], so it drains its active sessions and
+    /// stops the moment [`Vm::shutdown`] is called.
+    async fn start_ship_server(&self) {
+        if !state_history::ship_enabled_from_env() {
+            info!("state history server disabled (set PULSEVM_SHIP_ENABLED=1 to enable)");
+            return;
+        }
+
+        let mut ship_server = self.ship_server.write().await;
+        if ship_server.is_some() {
+            return;
+        }
+
+        let bind = state_history::ship_bind_addr_from_env();
+        let service = state_history::StateHistoryServer::new(self.clone());
+        let cancel = self.cancel.clone();
+        *ship_server = Some(tokio::spawn(async move {
+            if let Err(e) = service.run_ws_server(&bind, cancel).await {
+                spdlog::error!("state history server error: {:?}", e);
+            }
+        }));
+    }
+
+    /// Awaits the SHIP server's background task, if one was started. Used on
+    /// shutdown so the process doesn't exit out from under an in-flight
+    /// drain of active sessions.
+    pub async fn join_ship_server(&self) {
+        let handle = self.ship_server.write().await.take();
+        if let Some(handle) = handle {
+            let _ = handle.await;
+        }
+    }
+}
+
+/// A single-sample counter `MetricFamily`, the shape most of the `gather`
+/// output takes.
+fn counter_family(name: &str, help: &str, value: f64) -> client::MetricFamily {
+    client::MetricFamily {
+        name: name.to_string(),
+        help: help.to_string(),
+        r#type: client::MetricType::Counter as i32,
+        metric: vec![client::Metric {
+            counter: Some(client::Counter {
+                value,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        unit: String::new(),
+    }
+}
+
+/// A single-sample gauge `MetricFamily`.
+fn gauge_family(name: &str, help: &str, value: f64) -> client::MetricFamily {
+    client::MetricFamily {
+        name: name.to_string(),
+        help: help.to_string(),
+        r#type: client::MetricType::Gauge as i32,
+        metric: vec![client::Metric {
+            gauge: Some(client::Gauge {
+                value,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        unit: String::new(),
+    }
 }
 
 #[tonic::async_trait]
@@ -210,6 +283,8 @@ impl Vm for VirtualMachine {
         let mut block_timer = block_timer.write().await;
         block_timer.start(server_addr.clone()).await;
 
+        self.start_ship_server().await;
+
         let last_accepted_block_id = controller.last_accepted_block().id().map_err(|e| {
             Status::internal(format!("could not get last accepted block id: {}", e))
         })?;
@@ -292,6 +367,7 @@ impl Vm for VirtualMachine {
         controller
             .shutdown()
             .map_err(|e| Status::internal(format!("could not shutdown controller: {}", e)));
+        self.cancel.cancel();
         Ok(Response::new(()))
     }
 
@@ -319,7 +395,12 @@ impl Vm for VirtualMachine {
             .clone()
             .try_into()
             .map_err(|_| Status::invalid_argument("invalid node id"))?;
-        network_manager.connected(node_id);
+        let version = chain::PeerVersion::new(
+            request.get_ref().major,
+            request.get_ref().minor,
+            request.get_ref().patch,
+        );
+        network_manager.connected(node_id, version);
         Ok(Response::new(()))
     }
 
@@ -442,17 +523,26 @@ impl Vm for VirtualMachine {
         request: Request<vm::BlockVerifyRequest>,
     ) -> Result<tonic::Response<vm::BlockVerifyResponse>, Status> {
         debug!("block_verify called, verifying block...");
-        let mut controller = self.controller.write().await;
-        let mut mempool = self.mempool.write().await;
-        let block = match controller.parse_block(&request.get_ref().bytes) {
-            Ok(block) => block,
-            Err(e) => {
-                warn!("failed parsing block for verification: {}", e);
 
-                return Err(Status::internal("could not parse block"));
+        // Parsing only needs a read lock -- `Controller::parse_block` takes
+        // `&self` -- so do it before taking the write lock below, instead of
+        // holding that lock for the read-only step and blocking every other
+        // reader for no reason.
+        let block = {
+            let controller = self.controller.read().await;
+            match controller.parse_block(&request.get_ref().bytes) {
+                Ok(block) => block,
+                Err(e) => {
+                    warn!("failed parsing block for verification: {}", e);
+
+                    return Err(Status::internal("could not parse block"));
+                }
             }
         };
 
+        let mut controller = self.controller.write().await;
+        let mut mempool = self.mempool.write().await;
+
         // Verify the block
         match controller.verify_block(&block, &mut mempool).await {
             Ok(_) => {
@@ -618,7 +708,57 @@ impl Vm for VirtualMachine {
         &self,
         _request: Request<()>,
     ) -> Result<tonic::Response<vm::GatherResponse>, Status> {
-        Ok(Response::new(vm::GatherResponse::default()))
+        let controller = self.controller.read().await;
+        let mempool = self.mempool.read().await;
+        let metrics = controller.metrics();
+
+        let mut metric_families = vec![
+            counter_family(
+                "pulsevm_blocks_produced_total",
+                "Total number of blocks accepted onto the chain.",
+                metrics.blocks_produced() as f64,
+            ),
+            counter_family(
+                "pulsevm_transactions_applied_total",
+                "Total number of transactions committed to the chain.",
+                metrics.transactions_applied() as f64,
+            ),
+            gauge_family(
+                "pulsevm_mempool_depth",
+                "Number of transactions currently sitting in the mempool.",
+                mempool.len() as f64,
+            ),
+            gauge_family(
+                "pulsevm_transaction_apply_time_microseconds_average",
+                "Average wall-clock time spent applying a transaction, in microseconds.",
+                metrics.average_apply_time_us(),
+            ),
+        ];
+
+        let rejected_metrics = metrics
+            .transactions_rejected()
+            .iter()
+            .map(|(reason, count)| client::Metric {
+                label: vec![client::LabelPair {
+                    name: "reason".to_string(),
+                    value: reason.clone(),
+                }],
+                counter: Some(client::Counter {
+                    value: *count as f64,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect();
+        metric_families.push(client::MetricFamily {
+            name: "pulsevm_transactions_rejected_total".to_string(),
+            help: "Total number of transactions dropped instead of being included in a block, by reason.".to_string(),
+            r#type: client::MetricType::Counter as i32,
+            metric: rejected_metrics,
+            unit: String::new(),
+        });
+
+        Ok(Response::new(vm::GatherResponse { metric_families }))
     }
 
     async fn get_ancestors(
@@ -773,7 +913,7 @@ impl Vm for VirtualMachine {
         &self,
         request: Request<()>,
     ) -> Result<tonic::Response<vm::GetOngoingSyncStateSummaryResponse>, Status> {
-        info!("received request: {:?}", request);
+        debug!("received request: {:?}", request);
         Ok(Response::new(
             vm::GetOngoingSyncStateSummaryResponse::default(),
         ))
@@ -783,23 +923,50 @@ impl Vm for VirtualMachine {
         &self,
         request: Request<()>,
     ) -> Result<tonic::Response<vm::GetLastStateSummaryResponse>, Status> {
-        info!("received request: {:?}", request);
-        Ok(Response::new(vm::GetLastStateSummaryResponse::default()))
+        debug!("received request: {:?}", request);
+        let controller = self.controller.read().await;
+        let summary = controller
+            .get_last_state_summary()
+            .map_err(|e| Status::internal(format!("could not build state summary: {}", e)))?;
+        let bytes = summary
+            .to_bytes()
+            .map_err(|e| Status::internal(format!("could not pack state summary: {}", e)))?;
+        Ok(Response::new(vm::GetLastStateSummaryResponse {
+            id: summary.id.as_bytes().to_vec().into(),
+            height: summary.height,
+            bytes: bytes.into(),
+            err: vm::Error::Unspecified as i32,
+        }))
     }
 
     async fn parse_state_summary(
         &self,
         request: Request<vm::ParseStateSummaryRequest>,
     ) -> Result<tonic::Response<vm::ParseStateSummaryResponse>, Status> {
-        info!("received request: {:?}", request);
-        Ok(Response::new(vm::ParseStateSummaryResponse::default()))
+        debug!("received request: {:?}", request);
+        let controller = self.controller.read().await;
+        let summary = match controller.parse_state_summary(&request.get_ref().bytes) {
+            Ok(summary) => summary,
+            Err(_) => {
+                return Ok(Response::new(vm::ParseStateSummaryResponse {
+                    id: vec![].into(),
+                    height: 0,
+                    err: vm::Error::NotFound as i32,
+                }));
+            }
+        };
+        Ok(Response::new(vm::ParseStateSummaryResponse {
+            id: summary.id.as_bytes().to_vec().into(),
+            height: summary.height,
+            err: vm::Error::Unspecified as i32,
+        }))
     }
 
     async fn get_state_summary(
         &self,
         request: Request<vm::GetStateSummaryRequest>,
     ) -> Result<tonic::Response<vm::GetStateSummaryResponse>, Status> {
-        info!("received request: {:?}", request);
+        debug!("received request: {:?}", request);
         Ok(Response::new(vm::GetStateSummaryResponse::default()))
     }
 
@@ -807,7 +974,7 @@ impl Vm for VirtualMachine {
         &self,
         request: Request<vm::StateSummaryAcceptRequest>,
     ) -> Result<tonic::Response<vm::StateSummaryAcceptResponse>, Status> {
-        info!("received request: {:?}", request);
+        debug!("received request: {:?}", request);
         Ok(Response::new(vm::StateSummaryAcceptResponse::default()))
     }
 }
@@ -827,11 +994,18 @@ impl Http for VirtualMachine {
     ) -> Result<tonic::Response<http::HandleSimpleHttpResponse>, Status> {
         let body = std::str::from_utf8(request.get_ref().body.as_slice())
             .map_err(|_| Status::invalid_argument("invalid utf-8"))?;
-        let resp = self
-            .rpc_service
-            .handle_api_request(&body)
-            .await
-            .map_err(|_| Status::internal("failed to handle API request"))?;
+        let wants_canonical_json = request.get_ref().request_headers.iter().any(|h| {
+            h.key.eq_ignore_ascii_case("X-Canonical-Json")
+                && h.values
+                    .iter()
+                    .any(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        });
+        let resp = if wants_canonical_json {
+            self.rpc_service.handle_api_request_canonical(&body).await
+        } else {
+            self.rpc_service.handle_api_request(&body).await
+        }
+        .map_err(|_| Status::internal("failed to handle API request"))?;
         Ok(Response::new(http::HandleSimpleHttpResponse {
             code: 200,
             headers: vec![Element {
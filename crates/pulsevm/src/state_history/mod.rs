@@ -8,11 +8,37 @@ use pulsevm_core::controller::Controller;
 use tokio::{
     net::TcpListener as TokioTcpListener,
     sync::{RwLock, Semaphore},
+    task::JoinSet,
 };
 use tokio_util::sync::CancellationToken;
 
 use crate::{VirtualMachine, state_history::session::Session};
 
+/// Whether the SHIP (state-history) websocket server should run at all.
+/// Off by default: most deployments don't need history streaming, and it's
+/// one more open port to secure.
+pub fn ship_enabled_from_env() -> bool {
+    parse_bool_env("PULSEVM_SHIP_ENABLED").unwrap_or(false)
+}
+
+/// The address the SHIP server binds to when enabled.
+pub fn ship_bind_addr_from_env() -> String {
+    std::env::var("PULSEVM_SHIP_BIND").unwrap_or_else(|_| "0.0.0.0:9090".into())
+}
+
+fn parse_bool_env(name: &str) -> Option<bool> {
+    match std::env::var(name)
+        .ok()?
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
 #[derive(Clone)]
 pub struct StateHistoryServer {
     controller: Arc<RwLock<Controller>>,
@@ -25,6 +51,24 @@ impl StateHistoryServer {
         }
     }
 
+    /// Runs [`Self::run_ws_server`] if `enabled`, otherwise returns
+    /// immediately without ever binding `bind`. Split out from
+    /// `run_ws_server` so the enable/disable decision is unit-testable
+    /// without pulling in a real `VirtualMachine`.
+    pub async fn run_if_enabled(
+        &self,
+        enabled: bool,
+        bind: &str,
+        cancel: CancellationToken,
+    ) -> anyhow::Result<()> {
+        if !enabled {
+            spdlog::info!("state history server: disabled, not binding {}", bind);
+            return Ok(());
+        }
+
+        self.run_ws_server(bind, cancel).await
+    }
+
     pub async fn run_ws_server(&self, bind: &str, cancel: CancellationToken) -> anyhow::Result<()> {
         let listener = TokioTcpListener::bind(bind).await?;
         spdlog::info!("WebSocket listening on {}", bind);
@@ -32,6 +76,8 @@ impl StateHistoryServer {
         // TODO: Limit concurrent connections
         let _permits = Arc::new(Semaphore::new(1024));
 
+        let mut sessions = JoinSet::new();
+
         loop {
             tokio::select! {
                 _ = cancel.cancelled() => {
@@ -43,9 +89,10 @@ impl StateHistoryServer {
                     let (stream, peer): (tokio::net::TcpStream, SocketAddr) = accept_res?;
                     stream.set_nodelay(true).ok();
                     let controller = self.controller.clone();
+                    let session_cancel = cancel.clone();
 
-                    tokio::spawn(async move {
-                        let mut session = Session::new(peer, controller);
+                    sessions.spawn(async move {
+                        let mut session = Session::new_with_cancellation(peer, controller, session_cancel);
                         if let Err(e) = session.start(stream).await {
                             eprintln!("{} conn error: {e:?}", peer);
                         }
@@ -53,6 +100,119 @@ impl StateHistoryServer {
                 }
             }
         }
+
+        // `cancel` is already triggered at this point, so every active
+        // session's read loop is about to close its WebSocket and return on
+        // its own; wait for them so we don't drop connections or in-flight
+        // sends out from under them.
+        spdlog::info!(
+            "state history server: draining {} active session(s)",
+            sessions.len()
+        );
+        while sessions.join_next().await.is_some() {}
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bool_env_accepts_common_truthy_and_falsy_spellings() {
+        assert_eq!(parse_bool_env("PULSEVM_TEST_BOOL_ENV_NOT_SET"), None);
+
+        for (value, expected) in [
+            ("1", true),
+            ("true", true),
+            ("True", true),
+            ("yes", true),
+            ("on", true),
+            ("0", false),
+            ("false", false),
+            ("no", false),
+            ("off", false),
+        ] {
+            unsafe {
+                std::env::set_var("PULSEVM_TEST_BOOL_ENV", value);
+            }
+            assert_eq!(
+                parse_bool_env("PULSEVM_TEST_BOOL_ENV"),
+                Some(expected),
+                "value {value:?} should parse to {expected}"
+            );
+        }
+
+        unsafe {
+            std::env::set_var("PULSEVM_TEST_BOOL_ENV", "not-a-bool");
+        }
+        assert_eq!(parse_bool_env("PULSEVM_TEST_BOOL_ENV"), None);
+
+        unsafe {
+            std::env::remove_var("PULSEVM_TEST_BOOL_ENV");
+        }
+    }
+
+    #[tokio::test]
+    async fn run_if_enabled_does_not_bind_the_address_when_disabled() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener); // free the port up for the server (or not) to bind
+
+        let server = StateHistoryServer {
+            controller: Arc::new(RwLock::new(Controller::new())),
+        };
+        let cancel = CancellationToken::new();
+
+        server
+            .run_if_enabled(false, &addr.to_string(), cancel)
+            .await
+            .unwrap();
+
+        // Nothing should have bound `addr`: we can still bind it ourselves.
+        tokio::net::TcpListener::bind(addr)
+            .await
+            .expect("disabled server must not have bound the configured address");
+    }
+
+    #[tokio::test]
+    async fn run_if_enabled_binds_the_configured_address_when_enabled() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let server = StateHistoryServer {
+            controller: Arc::new(RwLock::new(Controller::new())),
+        };
+        let cancel = CancellationToken::new();
+        let server_cancel = cancel.clone();
+        let addr_string = addr.to_string();
+        let handle = tokio::spawn(async move {
+            server
+                .run_if_enabled(true, &addr_string, server_cancel)
+                .await
+        });
+
+        // Give the server a moment to actually bind before we probe it.
+        let mut connected = false;
+        for _ in 0..50 {
+            if tokio::net::TcpStream::connect(addr).await.is_ok() {
+                connected = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(
+            connected,
+            "enabled server never bound the configured address"
+        );
+
+        cancel.cancel();
+        tokio::time::timeout(std::time::Duration::from_secs(1), handle)
+            .await
+            .expect("server did not shut down after cancellation")
+            .unwrap()
+            .unwrap();
+    }
+}
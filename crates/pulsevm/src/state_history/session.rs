@@ -22,8 +22,9 @@ use tokio::{
     },
     task::JoinHandle,
 };
-use tokio_tungstenite::accept_async;
-use tungstenite::Message;
+use tokio_tungstenite::accept_async_with_config;
+use tokio_util::sync::CancellationToken;
+use tungstenite::{Message, protocol::WebSocketConfig};
 
 use crate::state_history::{
     request::RequestType,
@@ -33,6 +34,29 @@ use crate::state_history::{
     },
 };
 
+/// The lowest block number trace data is ever kept from, matching the
+/// `trace_begin_block: 1` this session reports in [`Session::get_status`].
+const TRACE_BEGIN_BLOCK: u32 = 1;
+
+/// How often [`Session::new`] sends a WebSocket ping to keep the connection
+/// alive behind proxies that drop idle connections.
+const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long [`Session::new`] waits for a pong (or any other message) after a
+/// ping before giving up on the connection and closing it.
+const DEFAULT_PONG_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// The largest complete WebSocket message [`Session::new`] accepts, in bytes.
+/// A client asking for more traces/deltas than fit in this budget should
+/// shrink its `get_blocks_request` instead of having the server buffer an
+/// unbounded amount of memory on its behalf.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 << 20;
+
+/// The largest single WebSocket frame [`Session::new`] accepts, in bytes.
+/// Kept well below [`DEFAULT_MAX_MESSAGE_SIZE`] since legitimate SHIP
+/// requests are small and only ever arrive as a single frame.
+const DEFAULT_MAX_FRAME_SIZE: usize = 4 << 20;
+
 pub struct Session {
     peer: SocketAddr,
     controller: Arc<RwLock<Controller>>,
@@ -41,10 +65,100 @@ pub struct Session {
     // streaming control
     stream_cancel: Option<Sender<()>>,
     stream_handle: Option<JoinHandle<()>>,
+    // keepalive
+    ping_interval: Duration,
+    pong_timeout: Duration,
+    // frame/message size limits
+    max_message_size: usize,
+    max_frame_size: usize,
+    // shutdown
+    cancel: CancellationToken,
 }
 
 impl Session {
     pub fn new(peer: SocketAddr, controller: Arc<RwLock<Controller>>) -> Self {
+        Self::with_keepalive(
+            peer,
+            controller,
+            DEFAULT_PING_INTERVAL,
+            DEFAULT_PONG_TIMEOUT,
+        )
+    }
+
+    /// Same as [`Session::new`], but the session exits and closes its
+    /// WebSocket as soon as `cancel` is triggered, instead of only on its own
+    /// read loop ending. Used by [`crate::state_history::StateHistoryServer`]
+    /// so active sessions drain cleanly on VM shutdown.
+    pub fn new_with_cancellation(
+        peer: SocketAddr,
+        controller: Arc<RwLock<Controller>>,
+        cancel: CancellationToken,
+    ) -> Self {
+        Self::with_cancellation(
+            peer,
+            controller,
+            DEFAULT_PING_INTERVAL,
+            DEFAULT_PONG_TIMEOUT,
+            DEFAULT_MAX_MESSAGE_SIZE,
+            DEFAULT_MAX_FRAME_SIZE,
+            cancel,
+        )
+    }
+
+    /// Same as [`Session::new`], but with a configurable ping interval and
+    /// pong timeout. Mainly useful for tests that don't want to wait out the
+    /// default 30s/90s keepalive schedule.
+    pub fn with_keepalive(
+        peer: SocketAddr,
+        controller: Arc<RwLock<Controller>>,
+        ping_interval: Duration,
+        pong_timeout: Duration,
+    ) -> Self {
+        Self::with_limits(
+            peer,
+            controller,
+            ping_interval,
+            pong_timeout,
+            DEFAULT_MAX_MESSAGE_SIZE,
+            DEFAULT_MAX_FRAME_SIZE,
+        )
+    }
+
+    /// Same as [`Session::with_keepalive`], but with configurable message and
+    /// frame size limits. Mainly useful for tests that want to exercise the
+    /// oversized-frame rejection without sending megabytes of data.
+    pub fn with_limits(
+        peer: SocketAddr,
+        controller: Arc<RwLock<Controller>>,
+        ping_interval: Duration,
+        pong_timeout: Duration,
+        max_message_size: usize,
+        max_frame_size: usize,
+    ) -> Self {
+        Self::with_cancellation(
+            peer,
+            controller,
+            ping_interval,
+            pong_timeout,
+            max_message_size,
+            max_frame_size,
+            CancellationToken::new(),
+        )
+    }
+
+    /// Same as [`Session::with_limits`], but with a configurable
+    /// [`CancellationToken`]. Mainly useful for tests that want to exercise
+    /// shutdown without going through [`Session::new_with_cancellation`]'s
+    /// defaults.
+    pub fn with_cancellation(
+        peer: SocketAddr,
+        controller: Arc<RwLock<Controller>>,
+        ping_interval: Duration,
+        pong_timeout: Duration,
+        max_message_size: usize,
+        max_frame_size: usize,
+        cancel: CancellationToken,
+    ) -> Self {
         Self {
             peer,
             controller,
@@ -52,11 +166,24 @@ impl Session {
             to_send_block_num: 0,
             stream_cancel: None,
             stream_handle: None,
+            ping_interval,
+            pong_timeout,
+            max_message_size,
+            max_frame_size,
+            cancel,
         }
     }
 
     pub async fn start(&mut self, stream: tokio::net::TcpStream) -> Result<()> {
-        let ws = accept_async(stream).await?;
+        let ws = accept_async_with_config(
+            stream,
+            Some(WebSocketConfig {
+                max_message_size: Some(self.max_message_size),
+                max_frame_size: Some(self.max_frame_size),
+                ..Default::default()
+            }),
+        )
+        .await?;
 
         // Split socket once; dedicate a writer task fed by mpsc
         let (mut sink, mut reader) = ws.split();
@@ -80,8 +207,45 @@ impl Session {
         // messages-in-flight budget (incremented by ACKs)
         let in_flight_budget = Arc::new(AtomicI64::new(0));
 
-        while let Some(msg) = reader.next().await {
-            let msg = msg?;
+        // keepalive: send a ping every `ping_interval`, and close if we
+        // haven't heard anything back -- a pong or otherwise -- within
+        // `pong_timeout` of the last one. `last_seen` resets on every
+        // inbound message, not just pongs, so an active reader never gets
+        // disconnected just because it's busy streaming rather than
+        // replying to pings promptly.
+        let mut last_seen = tokio::time::Instant::now();
+        let mut ping_ticker = tokio::time::interval(self.ping_interval);
+        ping_ticker.tick().await; // the first tick fires immediately; skip it
+
+        loop {
+            let msg = tokio::select! {
+                _ = self.cancel.cancelled() => {
+                    info!("{} ship session shutting down, closing connection", self.peer);
+                    let _ = tx_out.send(Message::Close(None)).await;
+                    break;
+                }
+                _ = ping_ticker.tick() => {
+                    if last_seen.elapsed() > self.pong_timeout {
+                        warn!("{} ship keepalive timed out, closing connection", self.peer);
+                        let _ = tx_out.send(Message::Close(None)).await;
+                        break;
+                    }
+                    let _ = tx_out.send(Message::Ping(Vec::new())).await;
+                    continue;
+                }
+                msg = reader.next() => match msg {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(tungstenite::Error::Capacity(e))) => {
+                        warn!("{} oversized ship frame ({e}), closing connection", self.peer);
+                        let _ = tx_out.send(Message::Close(None)).await;
+                        break;
+                    }
+                    Some(Err(e)) => return Err(e.into()),
+                    None => break,
+                },
+            };
+            last_seen = tokio::time::Instant::now();
+
             match msg {
                 Message::Binary(b) => {
                     let req_type = RequestType::read(&b, &mut 0)
@@ -221,6 +385,9 @@ impl Session {
                 Message::Ping(p) => {
                     tx_out.send(Message::Pong(p)).await.ok();
                 }
+                Message::Pong(_) => {
+                    // last_seen was already bumped above; nothing else to do.
+                }
                 Message::Close(cf) => {
                     let _ = tx_out.send(Message::Close(cf)).await;
                     break;
@@ -252,8 +419,11 @@ impl Session {
     async fn get_status(&self) -> Result<GetStatusResult> {
         let controller = self.controller.read().await;
         let chain_id = controller.chain_id();
-        let head_block = controller.last_accepted_block();
-        let head_block_id = head_block.id()?;
+        let lib = controller.last_irreversible_block();
+        let lib_id = controller
+            .get_block_id(lib)
+            .await?
+            .unwrap_or(controller.last_accepted_block().id()?);
 
         // Serveable end is bounded by what is actually appended to block_log on disk.
         // last_accepted can run ~1 block ahead; advertising it as the head/end makes the
@@ -262,7 +432,7 @@ impl Session {
         let serveable_head_id = controller
             .get_block_id(serveable)
             .await?
-            .unwrap_or(head_block_id);
+            .unwrap_or_else(|| lib_id.clone());
 
         Ok(GetStatusResult {
             variant: 0,
@@ -271,10 +441,10 @@ impl Session {
                 block_id: serveable_head_id,
             },
             last_irreversible: BlockPosition {
-                block_num: head_block.block_num(),
-                block_id: head_block_id,
+                block_num: lib,
+                block_id: lib_id,
             },
-            trace_begin_block: 1,
+            trace_begin_block: TRACE_BEGIN_BLOCK,
             trace_end_block: serveable,
             chain_state_begin_block: 1,
             chain_state_end_block: serveable,
@@ -285,7 +455,17 @@ impl Session {
     pub async fn update_current_request(&mut self, req: &mut GetBlocksRequestV0) -> Result<()> {
         let controller = self.controller.read().await;
 
-        self.to_send_block_num = std::cmp::max(req.start_block_num, 1);
+        // Clamp to what this node can actually serve. A `start_block_num`
+        // above the current head is left alone rather than clamped down --
+        // that's a subscription for blocks that haven't been produced yet,
+        // not a request for unavailable past data, and the streaming loop
+        // in `start` already waits for those instead of erroring.
+        let (start_block_num, end_block_num) =
+            clamp_block_range(req.start_block_num, req.end_block_num, TRACE_BEGIN_BLOCK);
+        req.start_block_num = start_block_num;
+        req.end_block_num = end_block_num;
+
+        self.to_send_block_num = std::cmp::max(req.start_block_num, TRACE_BEGIN_BLOCK);
 
         for cp in req.have_positions.iter() {
             if req.start_block_num <= cp.block_num {
@@ -320,6 +500,34 @@ impl Session {
     }
 }
 
+/// Clamps a requested `[start_block_num, end_block_num]` range to what this
+/// node can serve. `end_block_num == 0` means "no end" (stream forever) and
+/// is left alone; a nonzero end that falls before the (already-clamped)
+/// start is raised up to it instead of describing an empty range.
+fn clamp_block_range(start_block_num: u32, end_block_num: u32, trace_begin: u32) -> (u32, u32) {
+    let start_block_num = std::cmp::max(start_block_num, trace_begin);
+    let end_block_num = if end_block_num != 0 && end_block_num < start_block_num {
+        start_block_num
+    } else {
+        end_block_num
+    };
+
+    (start_block_num, end_block_num)
+}
+
+/// The highest block number a session is allowed to advance to. Normally
+/// that's whatever has actually been appended to `block_log`
+/// (`serveable_head`); a session that set `irreversible_only` is further
+/// bounded by the last irreversible block, since nothing later is safe to
+/// hand out as final.
+fn effective_head(serveable_head: u32, last_irreversible: u32, irreversible_only: bool) -> u32 {
+    if irreversible_only {
+        std::cmp::min(serveable_head, last_irreversible)
+    } else {
+        serveable_head
+    }
+}
+
 // Builds a GetBlocksResponseV0 for a specific block number.
 // Replace internals with your real "get block by number" logic.
 // As-is, it waits until head >= block_num and then returns head as the block payload.
@@ -329,18 +537,24 @@ async fn make_block_response_for(
     block_num: u32,
 ) -> Result<GetBlocksResponseV0> {
     let controller = controller.read().await;
-    let head = controller.last_accepted_block();
+    let lib = controller.last_irreversible_block();
+    let lib_id = controller
+        .get_block_id(lib)
+        .await?
+        .unwrap_or(controller.last_accepted_block().id()?);
 
     // Serveability bound: only advertise/serve blocks actually appended to block_log.
     // last_accepted can run ~1 block ahead of what is on disk, and read_block would
     // then return NotFound, stalling the reader. Bound the head to the on-disk last block.
-    let serveable = controller.block_log().map(|l| l.last_block()).unwrap_or(0);
+    let serveable_head = controller.block_log().map(|l| l.last_block()).unwrap_or(0);
+    // A session that asked for `irreversible_only` gets bounded further, down to the
+    // last irreversible block -- nothing later is safe to hand out as final yet.
+    let serveable = effective_head(serveable_head, lib, request.irreversible_only);
 
     if serveable < block_num {
         return Err(anyhow!("block {block_num} not yet available"));
     }
 
-    let head_block_id = head.id()?;
     let this_block_id = controller.get_block_id(block_num).await?.ok_or(anyhow!(
         "block {block_num} not found, may not be available yet",
     ))?;
@@ -409,7 +623,7 @@ async fn make_block_response_for(
     let serveable_head_id = controller
         .get_block_id(serveable)
         .await?
-        .unwrap_or(head_block_id);
+        .unwrap_or_else(|| lib_id.clone());
 
     Ok(GetBlocksResponseV0 {
         variant: 1,
@@ -418,8 +632,8 @@ async fn make_block_response_for(
             block_id: serveable_head_id,
         },
         last_irreversible: BlockPosition {
-            block_num: head.block_num(),
-            block_id: head_block_id,
+            block_num: lib,
+            block_id: lib_id,
         },
         this_block: Some(BlockPosition {
             block_num,
@@ -431,3 +645,203 @@ async fn make_block_response_for(
         deltas: deltas,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_block_range_raises_a_start_below_trace_begin() {
+        let (start, end) = clamp_block_range(0, 0, TRACE_BEGIN_BLOCK);
+
+        assert_eq!(start, TRACE_BEGIN_BLOCK);
+        assert_eq!(end, 0);
+    }
+
+    #[test]
+    fn clamp_block_range_leaves_a_start_beyond_head_alone_to_wait_for_it() {
+        // 1_000 is far beyond anything produced so far -- it's a subscription
+        // for future blocks, not a request for unavailable past data, so it
+        // should not get clamped down.
+        let (start, end) = clamp_block_range(1_000, 0, TRACE_BEGIN_BLOCK);
+
+        assert_eq!(start, 1_000);
+        assert_eq!(end, 0);
+    }
+
+    #[test]
+    fn clamp_block_range_raises_a_backwards_end_up_to_start() {
+        let (start, end) = clamp_block_range(50, 10, TRACE_BEGIN_BLOCK);
+
+        assert_eq!(start, 50);
+        assert_eq!(end, 50);
+    }
+
+    #[test]
+    fn clamp_block_range_leaves_an_unbounded_end_alone() {
+        let (start, end) = clamp_block_range(50, 0, TRACE_BEGIN_BLOCK);
+
+        assert_eq!(start, 50);
+        assert_eq!(end, 0);
+    }
+
+    #[test]
+    fn effective_head_is_unbounded_head_when_not_irreversible_only() {
+        assert_eq!(effective_head(100, 80, false), 100);
+    }
+
+    #[test]
+    fn effective_head_is_bounded_by_last_irreversible_when_requested() {
+        // head is partially beyond last_irreversible: irreversible_only
+        // sessions must not stream into that not-yet-final range.
+        assert_eq!(effective_head(100, 80, true), 80);
+        // head hasn't caught up to last_irreversible yet: still bounded by
+        // what's actually on disk.
+        assert_eq!(effective_head(60, 80, true), 60);
+    }
+
+    async fn start_keepalive_test_session(
+        ping_interval: Duration,
+        pong_timeout: Duration,
+    ) -> (SocketAddr, JoinHandle<()>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let controller = Arc::new(RwLock::new(Controller::new()));
+        let handle = tokio::spawn(async move {
+            let (stream, peer) = listener.accept().await.unwrap();
+            let mut session =
+                Session::with_keepalive(peer, controller, ping_interval, pong_timeout);
+            let _ = session.start(stream).await;
+        });
+
+        (addr, handle)
+    }
+
+    #[tokio::test]
+    async fn keepalive_sends_a_ping_within_the_configured_interval() {
+        let (addr, _handle) =
+            start_keepalive_test_session(Duration::from_millis(30), Duration::from_secs(10)).await;
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .unwrap();
+
+        // First frame is always the ABI.
+        client.next().await.unwrap().unwrap();
+
+        let ping = tokio::time::timeout(Duration::from_secs(1), client.next())
+            .await
+            .expect("no ping arrived within the timeout")
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(ping, Message::Ping(_)),
+            "expected a ping, got {ping:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn keepalive_closes_the_connection_when_no_pong_arrives_within_the_timeout() {
+        let (addr, _handle) =
+            start_keepalive_test_session(Duration::from_millis(20), Duration::from_millis(60))
+                .await;
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .unwrap();
+
+        // First frame is always the ABI.
+        client.next().await.unwrap().unwrap();
+
+        // Deliberately never read again until well past pong_timeout, so the
+        // underlying tungstenite client never gets a chance to auto-pong the
+        // ping the server sent in the meantime: the server's timeout must
+        // fire on its own, with no pong ever having arrived.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let next = tokio::time::timeout(Duration::from_secs(1), client.next())
+            .await
+            .expect("server never responded after the pong timeout elapsed");
+        assert!(
+            matches!(next, None | Some(Ok(Message::Close(_))) | Some(Err(_))),
+            "expected the connection to be closed, got {next:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_frame_closes_the_connection_cleanly_instead_of_buffering() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let controller = Arc::new(RwLock::new(Controller::new()));
+        let _handle = tokio::spawn(async move {
+            let (stream, peer) = listener.accept().await.unwrap();
+            let mut session = Session::with_limits(
+                peer,
+                controller,
+                Duration::from_secs(10),
+                Duration::from_secs(10),
+                1024,
+                1024,
+            );
+            let _ = session.start(stream).await;
+        });
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .unwrap();
+
+        // First frame is always the ABI.
+        client.next().await.unwrap().unwrap();
+
+        // Well beyond the 1024-byte max_frame_size configured above.
+        client.send(Message::Binary(vec![0u8; 4096])).await.unwrap();
+
+        let next = tokio::time::timeout(Duration::from_secs(1), client.next())
+            .await
+            .expect("server never responded to the oversized frame");
+        assert!(
+            matches!(next, None | Some(Ok(Message::Close(_))) | Some(Err(_))),
+            "expected the connection to be closed cleanly, got {next:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn cancellation_closes_the_connection_and_the_session_exits() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let controller = Arc::new(RwLock::new(Controller::new()));
+        let cancel = CancellationToken::new();
+        let session_cancel = cancel.clone();
+        let session_handle = tokio::spawn(async move {
+            let (stream, peer) = listener.accept().await.unwrap();
+            let mut session = Session::new_with_cancellation(peer, controller, session_cancel);
+            session.start(stream).await
+        });
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .unwrap();
+
+        // First frame is always the ABI.
+        client.next().await.unwrap().unwrap();
+
+        cancel.cancel();
+
+        let next = tokio::time::timeout(Duration::from_secs(1), client.next())
+            .await
+            .expect("server never responded to the cancellation");
+        assert!(
+            matches!(next, None | Some(Ok(Message::Close(_))) | Some(Err(_))),
+            "expected the connection to be closed cleanly, got {next:?}"
+        );
+
+        tokio::time::timeout(Duration::from_secs(1), session_handle)
+            .await
+            .expect("session did not exit after cancellation")
+            .unwrap()
+            .unwrap();
+    }
+}
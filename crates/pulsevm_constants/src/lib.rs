@@ -31,3 +31,13 @@ pub const DEFAULT_MAX_BLOCK_CPU_USAGE: u32 = 200_000;
 pub const DEFAULT_TARGET_BLOCK_CPU_USAGE_PCT: u32 = 10 * PERCENT_1 as u32; // 10%
 pub const DEFAULT_MAX_TRANSACTION_CPU_USAGE: u32 = 3 * DEFAULT_MAX_BLOCK_CPU_USAGE / 4; // 75%
 pub const DEFAULT_MIN_TRANSACTION_CPU_USAGE: u32 = 100;
+
+// Bounds the console output an action's `prints`/`prints_l` calls can
+// accumulate, so a chatty or malicious contract can't blow up trace memory
+// during a produce loop.
+pub const DEFAULT_MAX_CONSOLE_OUTPUT_BYTES: usize = 4096;
+pub const CONSOLE_TRUNCATION_MARKER: &str = "...[truncated]";
+
+// Caps how many transactions a single block built by `build_block` includes,
+// regardless of remaining CPU/NET headroom.
+pub const MAX_TRANSACTIONS_PER_BLOCK: usize = 10_000;
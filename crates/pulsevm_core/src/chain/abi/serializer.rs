@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
 use pulsevm_crypto::{Bytes, FixedBytes};
 use pulsevm_error::ChainError;
@@ -18,6 +18,7 @@ use crate::{
 
 type TypeName = String;
 type UnpackFunction = fn(bytes: &[u8], pos: &mut usize) -> Result<Value, ReadError>;
+type PackFunction = fn(value: &Value, buffer: &mut Vec<u8>) -> Result<(), ChainError>;
 
 pub struct AbiSerializer {
     typedefs: HashMap<TypeName, TypeName>,
@@ -28,6 +29,7 @@ pub struct AbiSerializer {
     variants: HashMap<TypeName, AbiVariantDefinition>,
     action_results: HashMap<Name, TypeName>,
     built_in_types: HashMap<TypeName, UnpackFunction>,
+    built_in_pack_types: HashMap<TypeName, PackFunction>,
 }
 impl AbiSerializer {
     pub fn from_abi(abi: AbiDefinition) -> Result<Self, ChainError> {
@@ -38,6 +40,7 @@ impl AbiSerializer {
         }
 
         let built_in_types = builtin_types();
+        let built_in_pack_types = builtin_pack_types();
         let mut structs: HashMap<TypeName, AbiStructDefinition> =
             HashMap::with_capacity(abi.structs.len());
         let mut typedefs: HashMap<TypeName, TypeName> = HashMap::with_capacity(abi.types.len());
@@ -97,6 +100,7 @@ impl AbiSerializer {
             variants,
             action_results,
             built_in_types,
+            built_in_pack_types,
         })
     }
 
@@ -301,6 +305,120 @@ impl AbiSerializer {
         Ok(())
     }
 
+    /// The inverse of [`binary_to_variant`](Self::binary_to_variant): encode
+    /// a JSON value against `type_name` and append the packed bytes to
+    /// `buffer`, for `abi_json_to_bin`.
+    pub fn variant_to_binary(
+        &self,
+        type_name: &str,
+        value: &Value,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), ChainError> {
+        let rtype = self.resolve_type(type_name);
+        let ftype = fundamental_type(&rtype);
+
+        if let Some(ptype) = self.built_in_pack_types.get(ftype) {
+            return ptype(value, buffer);
+        }
+
+        if self.built_in_types.contains_key(ftype) {
+            return Err(ChainError::TransactionError(format!(
+                "encoding for built-in type '{}' is not supported",
+                ftype
+            )));
+        }
+
+        if is_array(&rtype) {
+            let arr = value.as_array().ok_or_else(|| {
+                ChainError::TransactionError(format!("expected an array for type '{}'", rtype))
+            })?;
+            push_packed(arr.len(), buffer)?;
+            for v in arr {
+                self.variant_to_binary(ftype, v, buffer)?;
+            }
+            return Ok(());
+        } else if is_optional(&rtype) {
+            if value.is_null() {
+                buffer.push(0);
+            } else {
+                buffer.push(1);
+                self.variant_to_binary(ftype, value, buffer)?;
+            }
+            return Ok(());
+        } else if let Some(variant) = self.variants.get(&rtype) {
+            let pair = value.as_array().filter(|a| a.len() == 2).ok_or_else(|| {
+                ChainError::TransactionError(format!(
+                    "variant '{}' must be encoded as a [\"type\", value] pair",
+                    rtype
+                ))
+            })?;
+            let variant_type = pair[0].as_str().ok_or_else(|| {
+                ChainError::TransactionError(format!(
+                    "variant '{}' type tag must be a string",
+                    rtype
+                ))
+            })?;
+            let select = variant
+                .types
+                .iter()
+                .position(|t| t == variant_type)
+                .ok_or_else(|| {
+                    ChainError::TransactionError(format!(
+                        "'{}' is not a type of variant '{}'",
+                        variant_type, rtype
+                    ))
+                })?;
+            push_packed(select, buffer)?;
+            self.variant_to_binary(variant_type, &pair[1], buffer)?;
+            return Ok(());
+        }
+
+        self._struct_to_binary(&rtype, value, buffer)
+    }
+
+    fn _struct_to_binary(
+        &self,
+        struct_name: &str,
+        value: &Value,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), ChainError> {
+        let st = match self.structs.get(struct_name) {
+            Some(s) => s,
+            None => {
+                return Err(ChainError::TransactionError(format!(
+                    "struct '{}' not found",
+                    struct_name
+                )));
+            }
+        };
+
+        if st.base != "" {
+            self._struct_to_binary(&self.resolve_type(&st.base), value, buffer)?;
+        }
+
+        let obj = value.as_object().ok_or_else(|| {
+            ChainError::TransactionError(format!("expected an object for struct '{}'", struct_name))
+        })?;
+
+        for field in st.fields.iter() {
+            let extension = field.type_name.ends_with('$');
+            let field_type = self.resolve_type(remove_bin_extension(field.type_name.as_ref()));
+
+            match obj.get(&field.name) {
+                Some(v) => self.variant_to_binary(&field_type, v, buffer)?,
+                None if extension => break,
+                None => {
+                    return Err(ChainError::TransactionError(format!(
+                        "missing field '{}' for struct '{}'",
+                        field.name, struct_name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn resolve_type(&self, type_name: &str) -> String {
         if let Some(t) = self.typedefs.get(type_name) {
             let mut i = self.typedefs.len();
@@ -319,6 +437,14 @@ impl AbiSerializer {
         }
         type_name.to_owned()
     }
+
+    /// The struct type declared for `action` in the ABI's `actions` list,
+    /// e.g. `"transfer"` for the token contract's `transfer` action. Used
+    /// by `abi_json_to_bin`/`abi_bin_to_json` to find what to encode/decode
+    /// an action's `data` against.
+    pub fn get_action_type(&self, action: &Name) -> Option<&str> {
+        self.actions.get(action).map(String::as_str)
+    }
 }
 
 fn builtin_types() -> HashMap<TypeName, UnpackFunction> {
@@ -476,6 +602,178 @@ fn builtin_types() -> HashMap<TypeName, UnpackFunction> {
     m
 }
 
+fn push_packed<T: pulsevm_serialization::Write>(
+    value: T,
+    buffer: &mut Vec<u8>,
+) -> Result<(), ChainError> {
+    let bytes = value
+        .pack()
+        .map_err(|e| ChainError::TransactionError(format!("failed to pack value: {:?}", e)))?;
+    buffer.extend_from_slice(&bytes);
+    Ok(())
+}
+
+fn value_as_i64(type_name: &str, value: &Value) -> Result<i64, ChainError> {
+    value
+        .as_i64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+        .ok_or_else(|| {
+            ChainError::TransactionError(format!("expected a {} value, got '{}'", type_name, value))
+        })
+}
+
+fn value_as_u64(type_name: &str, value: &Value) -> Result<u64, ChainError> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+        .ok_or_else(|| {
+            ChainError::TransactionError(format!("expected a {} value, got '{}'", type_name, value))
+        })
+}
+
+fn value_as_str<'a>(type_name: &str, value: &'a Value) -> Result<&'a str, ChainError> {
+    value.as_str().ok_or_else(|| {
+        ChainError::TransactionError(format!("expected a {} string, got '{}'", type_name, value))
+    })
+}
+
+fn builtin_pack_types() -> HashMap<TypeName, PackFunction> {
+    let mut m: HashMap<TypeName, PackFunction> = HashMap::new();
+    m.insert("bool".to_string(), |value, buffer| {
+        let b = value.as_bool().ok_or_else(|| {
+            ChainError::TransactionError(format!("expected a bool, got '{}'", value))
+        })?;
+        buffer.push(if b { 1 } else { 0 });
+        Ok(())
+    });
+    m.insert("int8".to_string(), |value, buffer| {
+        push_packed(value_as_i64("int8", value)? as i8, buffer)
+    });
+    m.insert("uint8".to_string(), |value, buffer| {
+        push_packed(value_as_u64("uint8", value)? as u8, buffer)
+    });
+    m.insert("int16".to_string(), |value, buffer| {
+        push_packed(value_as_i64("int16", value)? as i16, buffer)
+    });
+    m.insert("uint16".to_string(), |value, buffer| {
+        push_packed(value_as_u64("uint16", value)? as u16, buffer)
+    });
+    m.insert("int32".to_string(), |value, buffer| {
+        push_packed(value_as_i64("int32", value)? as i32, buffer)
+    });
+    m.insert("uint32".to_string(), |value, buffer| {
+        push_packed(value_as_u64("uint32", value)? as u32, buffer)
+    });
+    m.insert("int64".to_string(), |value, buffer| {
+        push_packed(value_as_i64("int64", value)?, buffer)
+    });
+    m.insert("uint64".to_string(), |value, buffer| {
+        push_packed(value_as_u64("uint64", value)?, buffer)
+    });
+    m.insert("varint32".to_string(), |value, buffer| {
+        push_packed(VarInt32(value_as_i64("varint32", value)? as i32), buffer)
+    });
+    m.insert("varuint32".to_string(), |value, buffer| {
+        push_packed(VarUint32(value_as_u64("varuint32", value)? as u32), buffer)
+    });
+
+    m.insert("float32".to_string(), |value, buffer| {
+        let f = value.as_f64().ok_or_else(|| {
+            ChainError::TransactionError(format!("expected a float32, got '{}'", value))
+        })?;
+        push_packed(f as f32, buffer)
+    });
+    m.insert("float64".to_string(), |value, buffer| {
+        let f = value.as_f64().ok_or_else(|| {
+            ChainError::TransactionError(format!("expected a float64, got '{}'", value))
+        })?;
+        push_packed(f, buffer)
+    });
+
+    m.insert("time_point".to_string(), |value, buffer| {
+        let tp = TimePoint::from_str(value_as_str("time_point", value)?)
+            .map_err(|e| ChainError::TransactionError(format!("invalid time_point: {}", e)))?;
+        push_packed(tp, buffer)
+    });
+    m.insert("time_point_sec".to_string(), |value, buffer| {
+        let tp = TimePointSec::from_str(value_as_str("time_point_sec", value)?)
+            .map_err(|e| ChainError::TransactionError(format!("invalid time_point_sec: {}", e)))?;
+        push_packed(tp, buffer)
+    });
+    m.insert("block_timestamp_type".to_string(), |value, buffer| {
+        let bt = BlockTimestamp::from_str(value_as_str("block_timestamp_type", value)?).map_err(
+            |e| ChainError::TransactionError(format!("invalid block_timestamp_type: {}", e)),
+        )?;
+        push_packed(bt, buffer)
+    });
+
+    m.insert("name".to_string(), |value, buffer| {
+        let name = Name::from_str(value_as_str("name", value)?)?;
+        push_packed(name, buffer)
+    });
+
+    m.insert("bytes".to_string(), |value, buffer| {
+        let decoded = hex::decode(value_as_str("bytes", value)?)
+            .map_err(|e| ChainError::TransactionError(format!("invalid bytes hex: {}", e)))?;
+        push_packed(Bytes::from(decoded), buffer)
+    });
+    m.insert("string".to_string(), |value, buffer| {
+        push_packed(value_as_str("string", value)?.to_string(), buffer)
+    });
+
+    m.insert("checksum160".to_string(), |value, buffer| {
+        push_packed(parse_fixed_bytes::<20>("checksum160", value)?, buffer)
+    });
+    m.insert("checksum256".to_string(), |value, buffer| {
+        push_packed(parse_fixed_bytes::<32>("checksum256", value)?, buffer)
+    });
+    m.insert("checksum512".to_string(), |value, buffer| {
+        push_packed(parse_fixed_bytes::<64>("checksum512", value)?, buffer)
+    });
+
+    m.insert("public_key".to_string(), |value, buffer| {
+        let key = PublicKey::from_str(value_as_str("public_key", value)?)?;
+        push_packed(key, buffer)
+    });
+    m.insert("signature".to_string(), |value, buffer| {
+        let sig = Signature::from_str(value_as_str("signature", value)?)?;
+        push_packed(sig, buffer)
+    });
+
+    m.insert("symbol".to_string(), |value, buffer| {
+        let symbol = Symbol::from_str(value_as_str("symbol", value)?)
+            .map_err(|e| ChainError::TransactionError(format!("invalid symbol: {}", e)))?;
+        push_packed(symbol, buffer)
+    });
+    m.insert("symbol_code".to_string(), |value, buffer| {
+        let code = SymbolCode::from_str(value_as_str("symbol_code", value)?)
+            .map_err(|e| ChainError::TransactionError(format!("invalid symbol_code: {}", e)))?;
+        push_packed(code, buffer)
+    });
+    m.insert("asset".to_string(), |value, buffer| {
+        let asset = Asset::from_str(value_as_str("asset", value)?)
+            .map_err(|e| ChainError::TransactionError(format!("invalid asset: {}", e)))?;
+        push_packed(asset, buffer)
+    });
+    m.insert("extended_asset".to_string(), |value, buffer| {
+        let extended_asset = ExtendedAsset::from_str(value_as_str("extended_asset", value)?)
+            .map_err(|e| ChainError::TransactionError(format!("invalid extended_asset: {}", e)))?;
+        push_packed(extended_asset, buffer)
+    });
+
+    m
+}
+
+fn parse_fixed_bytes<const N: usize>(
+    type_name: &str,
+    value: &Value,
+) -> Result<FixedBytes<N>, ChainError> {
+    let decoded = hex::decode(value_as_str(type_name, value)?)
+        .map_err(|e| ChainError::TransactionError(format!("invalid {} hex: {}", type_name, e)))?;
+    FixedBytes::try_from(decoded)
+        .map_err(|_| ChainError::TransactionError(format!("invalid {} length", type_name)))
+}
+
 fn remove_bin_extension<'a>(ty: &'a str) -> &'a str {
     ty.strip_suffix('$').unwrap_or(ty)
 }
@@ -555,6 +853,7 @@ fn fundamental_type<'a>(ty: &'a str) -> &'a str {
 #[cfg(test)]
 mod tests {
     use pulsevm_serialization::Write;
+    use serde_json::json;
 
     use crate::chain::abi::test_abi::PULSE_ABI;
 
@@ -580,4 +879,224 @@ mod tests {
             )
             .unwrap();
     }
+
+    const TOKEN_ABI: &str = r#"
+    {
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {
+                "name": "transfer",
+                "base": "",
+                "fields": [
+                    { "name": "from", "type": "name" },
+                    { "name": "to", "type": "name" },
+                    { "name": "quantity", "type": "asset" },
+                    { "name": "memo", "type": "string" }
+                ]
+            }
+        ],
+        "actions": [
+            { "name": "transfer", "type": "transfer", "ricardian_contract": "" }
+        ]
+    }"#;
+
+    #[test]
+    fn transfer_json_round_trips_through_bin_against_the_token_abi() {
+        let abi: AbiDefinition = serde_json::from_str(TOKEN_ABI).unwrap();
+        let serializer = AbiSerializer::from_abi(abi).unwrap();
+        assert!(serializer.validate().is_ok());
+
+        let args = json!({
+            "from": "alice",
+            "to": "bob",
+            "quantity": "1.0000 EOS",
+            "memo": "for lunch",
+        });
+
+        let action_type = serializer
+            .get_action_type(&Name::from_str("transfer").unwrap())
+            .unwrap()
+            .to_string();
+
+        let mut binargs = Vec::new();
+        serializer
+            .variant_to_binary(&action_type, &args, &mut binargs)
+            .unwrap();
+
+        let decoded = serializer
+            .binary_to_variant(&action_type, &binargs, &mut 0)
+            .unwrap();
+
+        assert_eq!(decoded, args);
+    }
+
+    const EXTENDED_TRANSFER_ABI: &str = r#"
+    {
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {
+                "name": "extransfer",
+                "base": "",
+                "fields": [
+                    { "name": "from", "type": "name" },
+                    { "name": "to", "type": "name" },
+                    { "name": "quantity", "type": "extended_asset" }
+                ]
+            }
+        ],
+        "actions": [
+            { "name": "extransfer", "type": "extransfer", "ricardian_contract": "" }
+        ]
+    }"#;
+
+    #[test]
+    fn extransfer_json_round_trips_through_bin_against_an_extended_transfer_abi() {
+        let abi: AbiDefinition = serde_json::from_str(EXTENDED_TRANSFER_ABI).unwrap();
+        let serializer = AbiSerializer::from_abi(abi).unwrap();
+        assert!(serializer.validate().is_ok());
+
+        let args = json!({
+            "from": "alice",
+            "to": "bob",
+            "quantity": "1.0000 EOS@eosio.token",
+        });
+
+        let action_type = serializer
+            .get_action_type(&Name::from_str("extransfer").unwrap())
+            .unwrap()
+            .to_string();
+
+        let mut binargs = Vec::new();
+        serializer
+            .variant_to_binary(&action_type, &args, &mut binargs)
+            .unwrap();
+
+        let decoded = serializer
+            .binary_to_variant(&action_type, &binargs, &mut 0)
+            .unwrap();
+
+        assert_eq!(decoded, args);
+    }
+
+    const TIMESTAMPS_ABI: &str = r#"
+    {
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {
+                "name": "logtime",
+                "base": "",
+                "fields": [
+                    { "name": "tp", "type": "time_point" },
+                    { "name": "tps", "type": "time_point_sec" },
+                    { "name": "bt", "type": "block_timestamp_type" }
+                ]
+            }
+        ],
+        "actions": [
+            { "name": "logtime", "type": "logtime", "ricardian_contract": "" }
+        ]
+    }"#;
+
+    #[test]
+    fn logtime_json_round_trips_through_bin_against_a_timestamps_abi() {
+        let abi: AbiDefinition = serde_json::from_str(TIMESTAMPS_ABI).unwrap();
+        let serializer = AbiSerializer::from_abi(abi).unwrap();
+        assert!(serializer.validate().is_ok());
+
+        let args = json!({
+            "tp": "2020-01-01T00:00:00.500Z",
+            "tps": "2020-01-01T00:00:00Z",
+            "bt": "2020-01-01T00:00:00.500",
+        });
+
+        let action_type = serializer
+            .get_action_type(&Name::from_str("logtime").unwrap())
+            .unwrap()
+            .to_string();
+
+        let mut binargs = Vec::new();
+        serializer
+            .variant_to_binary(&action_type, &args, &mut binargs)
+            .unwrap();
+
+        let decoded = serializer
+            .binary_to_variant(&action_type, &binargs, &mut 0)
+            .unwrap();
+
+        assert_eq!(decoded, args);
+    }
+
+    const UPDATEAUTH_ABI: &str = r#"
+    {
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {
+                "name": "key_weight",
+                "base": "",
+                "fields": [
+                    { "name": "key", "type": "public_key" },
+                    { "name": "weight", "type": "uint16" }
+                ]
+            },
+            {
+                "name": "authority",
+                "base": "",
+                "fields": [
+                    { "name": "threshold", "type": "uint32" },
+                    { "name": "keys", "type": "key_weight[]" }
+                ]
+            },
+            {
+                "name": "updateauth",
+                "base": "",
+                "fields": [
+                    { "name": "account", "type": "name" },
+                    { "name": "permission", "type": "name" },
+                    { "name": "parent", "type": "name" },
+                    { "name": "auth", "type": "authority" }
+                ]
+            }
+        ],
+        "actions": [
+            { "name": "updateauth", "type": "updateauth", "ricardian_contract": "" }
+        ]
+    }"#;
+
+    #[test]
+    fn updateauth_json_round_trips_through_bin_with_a_public_key_in_its_authority() {
+        let abi: AbiDefinition = serde_json::from_str(UPDATEAUTH_ABI).unwrap();
+        let serializer = AbiSerializer::from_abi(abi).unwrap();
+        assert!(serializer.validate().is_ok());
+
+        let args = json!({
+            "account": "alice",
+            "permission": "active",
+            "parent": "owner",
+            "auth": {
+                "threshold": 1,
+                "keys": [
+                    {
+                        "key": "PUB_K1_5bbkxaLdB5bfVZW6DJY8M74vwT2m61PqwywNUa5azfkJTvYa5H",
+                        "weight": 1
+                    }
+                ]
+            },
+        });
+
+        let action_type = serializer
+            .get_action_type(&Name::from_str("updateauth").unwrap())
+            .unwrap()
+            .to_string();
+
+        let mut binargs = Vec::new();
+        serializer
+            .variant_to_binary(&action_type, &args, &mut binargs)
+            .unwrap();
+
+        let decoded = serializer
+            .binary_to_variant(&action_type, &binargs, &mut 0)
+            .unwrap();
+
+        assert_eq!(decoded, args);
+    }
 }
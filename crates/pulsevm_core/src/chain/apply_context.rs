@@ -6,29 +6,32 @@ use std::{
 
 use chrono::Utc;
 use pulsevm_billable_size::billable_size_v;
+use pulsevm_constants::{CONSOLE_TRUNCATION_MARKER, DEFAULT_MAX_CONSOLE_OUTPUT_BYTES};
 use pulsevm_crypto::Bytes;
 use pulsevm_error::ChainError;
 use pulsevm_ffi::{
-    AccountMetadataObject, BlockTimestamp, ChainConfigV0, Database, Float128, Index64IteratorCache,
-    Index64Object, Index128IteratorCache, Index128Object, Index256IteratorCache, Index256Object,
-    IndexDoubleIteratorCache, IndexDoubleObject, IndexLongDoubleIteratorCache,
-    IndexLongDoubleObject, KeyValueIteratorCache, KeyValueObject, Microseconds, TableObject, U256,
+    AccountMetadataObject, BlockTimestamp, ChainConfigV0, Database, Float128,
+    Index128IteratorCache, Index128Object, Index256IteratorCache, Index256Object,
+    Index64IteratorCache, Index64Object, IndexDoubleIteratorCache, IndexDoubleObject,
+    IndexLongDoubleIteratorCache, IndexLongDoubleObject, KeyValueIteratorCache, KeyValueObject,
+    Microseconds, TableObject, U256,
 };
 use pulsevm_serialization::Write;
 
 use crate::{
-    CODE_NAME,
     chain::{
         authority::PermissionLevel,
         authorization_manager::AuthorizationManager,
         controller::Controller,
-        transaction::{Action, ActionReceipt, generate_action_digest},
+        id::Id,
+        transaction::{generate_action_digest, Action, ActionReceipt},
         transaction_context::TransactionContext,
         utils::pulse_assert,
         wasm_runtime::WasmRuntime,
     },
     name::Name,
     transaction::PackedTransaction,
+    CODE_NAME,
 };
 
 struct ApplyContextInner {
@@ -75,7 +78,7 @@ impl ApplyContext {
         action_ordinal: u32,
         depth: u32,
         cpu_limit: i64,
-        context_free: bool
+        context_free: bool,
     ) -> Result<Self, ChainError> {
         let pending_block_timestamp = trx_context.pending_block_timestamp()?;
 
@@ -166,7 +169,9 @@ impl ApplyContext {
     }
 
     pub fn exec_one(&mut self) -> Result<u64, ChainError> {
-        let receiver_account = self.db.get_account_metadata(self.receiver.as_u64())?;
+        let receiver_account = self
+            .trx_context
+            .get_account_metadata(self.receiver.clone())?;
         let mut cpu_used = 100; // Base usage is always 100 instructions
         let action = {
             let mut inner = self.inner.write()?;
@@ -202,7 +207,9 @@ impl ApplyContext {
             let inner = self.inner.read()?;
             generate_action_digest(&action, inner.action_return_value.clone())
         };
-        let first_receiver_account = self.db.get_account_metadata(action.account().as_u64())?;
+        let first_receiver_account = self
+            .trx_context
+            .get_account_metadata(action.account().clone())?;
         let mut receipt = ActionReceipt::new(
             self.receiver.clone(),
             act_digest,
@@ -238,6 +245,32 @@ impl ApplyContext {
         Ok(())
     }
 
+    /// Appends to this action's console output, truncating at
+    /// `DEFAULT_MAX_CONSOLE_OUTPUT_BYTES` with [`CONSOLE_TRUNCATION_MARKER`]
+    /// so a chatty contract's `prints`/`prints_l` calls can't blow up trace
+    /// memory. Once truncated, later calls are no-ops.
+    pub fn console_append(&mut self, data: &str) -> Result<(), ChainError> {
+        self.trx_context
+            .modify_action_trace(self.action_ordinal, |trace| {
+                if trace.console.len() > DEFAULT_MAX_CONSOLE_OUTPUT_BYTES {
+                    return;
+                }
+
+                let remaining = DEFAULT_MAX_CONSOLE_OUTPUT_BYTES - trace.console.len();
+                if data.len() <= remaining {
+                    trace.console.push_str(data);
+                    return;
+                }
+
+                let mut cut = remaining;
+                while cut > 0 && !data.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                trace.console.push_str(&data[..cut]);
+                trace.console.push_str(CONSOLE_TRUNCATION_MARKER);
+            })
+    }
+
     pub fn require_authorization(
         &self,
         account: &Name,
@@ -311,6 +344,12 @@ impl ApplyContext {
         self.db.is_account(account.as_u64())
     }
 
+    /// The account whose contract dispatched the currently-applying action
+    /// via an inline action, or the zero name for a top-level action.
+    pub fn get_sender(&self) -> Result<Name, ChainError> {
+        self.trx_context.get_sender(self.action_ordinal)
+    }
+
     pub fn execute_inline(&mut self, a: &Action) -> Result<(), ChainError> {
         let action = {
             let inner = self.inner.read()?;
@@ -523,6 +562,7 @@ impl ApplyContext {
             inner.keyval_cache.cache_table(&table)?;
             inner.keyval_cache.add(obj)?
         };
+        self.trx_context.note_iterator_opened()?;
 
         let billable_size = data.len() as i64 + billable_size_v::<KeyValueObject>() as i64;
         self.update_db_usage(&payer.into(), billable_size)?;
@@ -540,12 +580,9 @@ impl ApplyContext {
         let new_size = data.as_ref().len() as i64;
         let (old_size, old_payer, new_payer) = {
             let inner = self.inner.read()?;
-            let obj = inner.keyval_cache.get(iterator)?;
-            let table_obj = inner.keyval_cache.get_table(obj.get_table_id())?;
-            pulse_assert(
-                table_obj.get_code().to_uint64_t() == self.receiver.as_u64(),
-                ChainError::TransactionError(format!("db access violation",)),
-            )?;
+            let obj = inner
+                .keyval_cache
+                .get_checked(iterator, self.receiver.as_u64())?;
             let old_payer = obj.get_payer().to_uint64_t();
             let new_payer = if payer == 0 {
                 obj.get_payer().to_uint64_t()
@@ -674,6 +711,7 @@ impl ApplyContext {
             inner.index64_cache.cache_table(&table)?;
             inner.index64_cache.add(obj)?
         };
+        self.trx_context.note_iterator_opened()?;
 
         let billable_size = billable_size_v::<Index64Object>() as i64;
         self.update_db_usage(&payer.into(), billable_size)?;
@@ -691,12 +729,9 @@ impl ApplyContext {
         let billing_size = billable_size_v::<Index64Object>() as i64;
         let (old_payer, new_payer) = {
             let inner = self.inner.read()?;
-            let obj = inner.index64_cache.get(iterator)?;
-            let table_obj = inner.index64_cache.get_table(obj.get_table_id())?;
-            pulse_assert(
-                table_obj.get_code().to_uint64_t() == self.receiver.as_u64(),
-                ChainError::TransactionError(format!("db access violation",)),
-            )?;
+            let obj = inner
+                .index64_cache
+                .get_checked(iterator, self.receiver.as_u64())?;
             let old_payer = obj.get_payer().to_uint64_t();
             let new_payer = if payer == 0 {
                 obj.get_payer().to_uint64_t()
@@ -854,6 +889,7 @@ impl ApplyContext {
             inner.index128_cache.cache_table(&table)?;
             inner.index128_cache.add(obj)?
         };
+        self.trx_context.note_iterator_opened()?;
 
         let billable_size = billable_size_v::<Index128Object>() as i64;
         self.update_db_usage(&payer.into(), billable_size)?;
@@ -871,12 +907,9 @@ impl ApplyContext {
         let billing_size = billable_size_v::<Index128Object>() as i64;
         let (old_payer, new_payer) = {
             let inner = self.inner.read()?;
-            let obj = inner.index128_cache.get(iterator)?;
-            let table_obj = inner.index128_cache.get_table(obj.get_table_id())?;
-            pulse_assert(
-                table_obj.get_code().to_uint64_t() == self.receiver.as_u64(),
-                ChainError::TransactionError(format!("db access violation",)),
-            )?;
+            let obj = inner
+                .index128_cache
+                .get_checked(iterator, self.receiver.as_u64())?;
             let old_payer = obj.get_payer().to_uint64_t();
             let new_payer = if payer == 0 {
                 obj.get_payer().to_uint64_t()
@@ -1037,6 +1070,7 @@ impl ApplyContext {
             inner.index256_cache.cache_table(&table)?;
             inner.index256_cache.add(obj)?
         };
+        self.trx_context.note_iterator_opened()?;
 
         let billable_size = billable_size_v::<Index256Object>() as i64;
         self.update_db_usage(&payer.into(), billable_size)?;
@@ -1054,12 +1088,9 @@ impl ApplyContext {
         let billing_size = billable_size_v::<Index256Object>() as i64;
         let (old_payer, new_payer) = {
             let inner = self.inner.read()?;
-            let obj = inner.index256_cache.get(iterator)?;
-            let table_obj = inner.index256_cache.get_table(obj.get_table_id())?;
-            pulse_assert(
-                table_obj.get_code().to_uint64_t() == self.receiver.as_u64(),
-                ChainError::TransactionError(format!("db access violation",)),
-            )?;
+            let obj = inner
+                .index256_cache
+                .get_checked(iterator, self.receiver.as_u64())?;
             let old_payer = obj.get_payer().to_uint64_t();
             let new_payer = if payer == 0 {
                 obj.get_payer().to_uint64_t()
@@ -1220,6 +1251,7 @@ impl ApplyContext {
             inner.index_double_cache.cache_table(&table)?;
             inner.index_double_cache.add(obj)?
         };
+        self.trx_context.note_iterator_opened()?;
 
         let billable_size = billable_size_v::<IndexDoubleObject>() as i64;
         self.update_db_usage(&payer.into(), billable_size)?;
@@ -1237,12 +1269,9 @@ impl ApplyContext {
         let billing_size = billable_size_v::<IndexDoubleObject>() as i64;
         let (old_payer, new_payer) = {
             let inner = self.inner.read()?;
-            let obj = inner.index_double_cache.get(iterator)?;
-            let table_obj = inner.index_double_cache.get_table(obj.get_table_id())?;
-            pulse_assert(
-                table_obj.get_code().to_uint64_t() == self.receiver.as_u64(),
-                ChainError::TransactionError(format!("db access violation",)),
-            )?;
+            let obj = inner
+                .index_double_cache
+                .get_checked(iterator, self.receiver.as_u64())?;
             let old_payer = obj.get_payer().to_uint64_t();
             let new_payer = if payer == 0 {
                 obj.get_payer().to_uint64_t()
@@ -1413,6 +1442,7 @@ impl ApplyContext {
             inner.index_long_double_cache.cache_table(&table)?;
             inner.index_long_double_cache.add(obj)?
         };
+        self.trx_context.note_iterator_opened()?;
 
         let billable_size = billable_size_v::<IndexLongDoubleObject>() as i64;
         self.update_db_usage(&payer.into(), billable_size)?;
@@ -1430,14 +1460,9 @@ impl ApplyContext {
         let billing_size = billable_size_v::<IndexLongDoubleObject>() as i64;
         let (old_payer, new_payer) = {
             let inner = self.inner.read()?;
-            let obj = inner.index_long_double_cache.get(iterator)?;
-            let table_obj = inner
+            let obj = inner
                 .index_long_double_cache
-                .get_table(obj.get_table_id())?;
-            pulse_assert(
-                table_obj.get_code().to_uint64_t() == self.receiver.as_u64(),
-                ChainError::TransactionError(format!("db access violation",)),
-            )?;
+                .get_checked(iterator, self.receiver.as_u64())?;
             let old_payer = obj.get_payer().to_uint64_t();
             let new_payer = if payer == 0 {
                 obj.get_payer().to_uint64_t()
@@ -1675,6 +1700,14 @@ impl ApplyContext {
         &self.pending_block_timestamp
     }
 
+    pub fn action_ordinal(&self) -> u32 {
+        self.action_ordinal
+    }
+
+    pub fn trx_id(&self) -> Result<Id, ChainError> {
+        self.trx_context.trx_id()
+    }
+
     pub fn account_ram_deltas(&self) -> Result<BTreeMap<Name, i64>, ChainError> {
         let inner = self.inner.read()?;
         Ok(inner.account_ram_deltas.clone())
@@ -2,9 +2,9 @@ use std::{fmt, str::FromStr};
 
 use pulsevm_proc_macros::{NumBytes, Write};
 use pulsevm_serialization::{Read, ReadError};
-use serde::{Deserialize, Serialize, de};
+use serde::{de, Deserialize, Serialize};
 
-use crate::chain::asset::{MAX_PRECISION, Symbol, SymbolCode};
+use crate::chain::asset::{Symbol, SymbolCode, MAX_PRECISION};
 
 /// Matches nodeos `asset::max_amount`. Amounts are bounded well inside i64 so
 /// that addition of two valid assets cannot overflow.
@@ -40,7 +40,9 @@ impl Asset {
     pub fn try_new(amount: i64, symbol: Symbol) -> Result<Self, ParseAssetError> {
         let asset = Asset { amount, symbol };
         if !asset.is_amount_within_range() {
-            return Err(ParseAssetError("magnitude of asset amount must be less than 2^62".into()));
+            return Err(ParseAssetError(
+                "magnitude of asset amount must be less than 2^62".into(),
+            ));
         }
         if !asset.symbol.is_valid() {
             return Err(ParseAssetError("invalid symbol".into()));
@@ -206,10 +208,38 @@ impl FromStr for Asset {
 
 #[cfg(test)]
 mod tests {
-    use pulsevm_serialization::Write;
+    use pulsevm_name::Name;
+    use pulsevm_serialization::{Read as _, Write};
 
     use super::*;
 
+    #[test]
+    fn test_packing_heterogeneous_values_together() {
+        // `(T1, T2, T3)` already has `NumBytes`/`Read`/`Write`, so a tuple is
+        // the repo's existing "pack several distinct values at once" helper:
+        // no separate pack_many/unpack_many function is needed.
+        let sent = (
+            Name::from_str("alice").unwrap(),
+            Asset::new(123456, sys(4)),
+            "memo".to_string(),
+        );
+
+        let packed = sent.pack().unwrap();
+        let received = <(Name, Asset, String)>::read(&packed, &mut 0).unwrap();
+
+        assert_eq!(received, sent);
+    }
+
+    #[test]
+    fn test_packing_a_name_and_a_u64_tuple() {
+        let sent = (Name::from_str("alice").unwrap(), 123456789u64);
+
+        let packed = sent.pack().unwrap();
+        let received = <(Name, u64)>::read(&packed, &mut 0).unwrap();
+
+        assert_eq!(received, sent);
+    }
+
     fn sys(precision: u8) -> Symbol {
         Symbol::new_with_code(precision, SymbolCode::from_str("SYS").unwrap())
     }
@@ -242,7 +272,11 @@ mod tests {
             "1000000 USD",
             "0.1 CUR",
         ] {
-            assert_eq!(s.parse::<Asset>().unwrap().to_string(), s, "round trip: {s}");
+            assert_eq!(
+                s.parse::<Asset>().unwrap().to_string(),
+                s,
+                "round trip: {s}"
+            );
         }
     }
 
@@ -274,6 +308,23 @@ mod tests {
         assert!("1.0000".parse::<Asset>().is_err());
     }
 
+    #[test]
+    fn json_serializes_and_deserializes_with_symbol_precision() {
+        // RPC-facing JSON uses the "<amount scaled> <CODE>" string form, not
+        // the binary Read/Write encoding, and must render exactly
+        // `symbol.precision()` decimals rather than trimming trailing zeros.
+        let asset = Asset::new(
+            10000,
+            Symbol::new_with_code(4, SymbolCode::from_str("EOS").unwrap()),
+        );
+
+        let json = serde_json::to_string(&asset).unwrap();
+        assert_eq!(json, "\"1.0000 EOS\"");
+
+        let round_tripped: Asset = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, asset);
+    }
+
     #[test]
     fn test_asset_pack() {
         let asset = Asset::new(123456, sys(4));
@@ -281,4 +332,12 @@ mod tests {
         // amount: 123456 LE, then precision 4 + "SYS" packed into the symbol u64
         assert_eq!(packed, "40e20100000000000453595300000000");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_asset_has_a_const_size_since_every_field_is_fixed_size() {
+        use pulsevm_serialization::NumBytes;
+
+        assert_eq!(Asset::CONST_SIZE, Some(16));
+        assert_eq!(Asset::new(123456, sys(4)).num_bytes(), 16);
+    }
+}
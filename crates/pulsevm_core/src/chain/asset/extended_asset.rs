@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use pulsevm_proc_macros::{NumBytes, Read, Write};
 
@@ -18,3 +18,48 @@ impl fmt::Display for ExtendedAsset {
         write!(f, "{}@{}", self.quantity, self.contract)
     }
 }
+
+#[derive(Debug)]
+pub struct ParseExtendedAssetError(String);
+
+impl fmt::Display for ParseExtendedAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseExtendedAssetError {}
+
+impl FromStr for ExtendedAsset {
+    type Err = ParseExtendedAssetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (asset_str, contract_str) = s
+            .split_once('@')
+            .ok_or_else(|| ParseExtendedAssetError("expected format: \"1.0000 EOS@contract\"".into()))?;
+
+        let quantity = Asset::from_str(asset_str.trim())
+            .map_err(|e| ParseExtendedAssetError(format!("invalid asset: {}", e)))?;
+        let contract = Name::from_str(contract_str.trim())
+            .map_err(|e| ParseExtendedAssetError(format!("invalid contract name: {}", e)))?;
+
+        Ok(ExtendedAsset { quantity, contract })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_round_trips_through_from_str() {
+        let s = "1.0000 EOS@eosio.token";
+        assert_eq!(s.parse::<ExtendedAsset>().unwrap().to_string(), s);
+    }
+
+    #[test]
+    fn rejects_missing_contract() {
+        assert!("1.0000 EOS".parse::<ExtendedAsset>().is_err());
+    }
+}
@@ -1,10 +1,9 @@
 use std::collections::VecDeque;
 
-use pulsevm_crypto::{Digest, FixedBytes};
+use pulsevm_crypto::{hash_packed, Digest};
 use pulsevm_error::ChainError;
 use pulsevm_ffi::{BlockTimestamp, Database};
 use pulsevm_proc_macros::{NumBytes, Read, Write};
-use pulsevm_serialization::Write;
 use serde::{Serialize, ser::SerializeStruct};
 use spdlog::{info, warn};
 
@@ -29,10 +28,7 @@ pub struct BlockHeader {
 
 impl BlockHeader {
     fn digest(&self) -> Result<Digest, ChainError> {
-        let packed = self
-            .pack()
-            .map_err(|e| ChainError::SerializationError(e.to_string()))?;
-        Ok(Digest::hash(&packed))
+        hash_packed(self).map_err(|e| ChainError::SerializationError(e.to_string()))
     }
 
     fn block_num(&self) -> u32 {
@@ -41,23 +37,13 @@ impl BlockHeader {
 
     #[inline]
     pub fn num_from_id(id: &Id) -> u32 {
-        // First 4 bytes contain the block number in big-endian.
-        u32::from_be_bytes(id.0.0[0..4].try_into().unwrap())
-    }
-
-    #[inline]
-    pub fn id_from_num(id: &Id) -> u32 {
-        // First 4 bytes contain the block number in big-endian.
-        u32::from_be_bytes(id.0.0[0..4].try_into().unwrap())
+        id.block_num()
     }
 
     #[inline]
     pub fn calculate_id(&self) -> Result<Id, ChainError> {
-        let mut result = self.digest()?; // exclude producer_signature etc.
-        let bn_be = self.block_num().to_be_bytes(); // endian_reverse_u32 on LE == write BE bytes
-        // Overwrite the first 4 bytes with the big-endian block number
-        result.0[0..4].copy_from_slice(&bn_be);
-        Ok(Id(FixedBytes(result.0)))
+        let digest = self.digest()?; // exclude producer_signature etc.
+        Ok(Id::with_block_num(self.block_num(), digest.0))
     }
 
     pub fn validate(&self, db: &Database) -> Result<(), ChainError> {
@@ -221,7 +207,7 @@ impl Serialize for SignedBlock {
 mod tests {
     use pulsevm_serialization::{Read, Write};
 
-    use crate::block::SignedBlock;
+    use crate::block::{BlockHeader, SignedBlock};
 
     #[test]
     pub fn test_block_serialization() {
@@ -229,4 +215,22 @@ mod tests {
         let packed = signed_block.pack().unwrap();
         let _ = SignedBlock::read(&packed, &mut 0).unwrap();
     }
+
+    #[test]
+    fn test_block_ids_sort_ascending_by_height() {
+        // The block number is stored big-endian in the first 4 bytes of the
+        // id on purpose, so that a byte-ordered range scan over ids (as
+        // chainbase's block-by-id index does) visits blocks in ascending
+        // height order.
+        let mut header = BlockHeader::default();
+        let genesis_id = header.calculate_id().unwrap();
+        header.previous = genesis_id.clone();
+        let next_id = header.calculate_id().unwrap();
+
+        assert_eq!(
+            BlockHeader::num_from_id(&genesis_id) + 1,
+            header.block_num()
+        );
+        assert!(genesis_id.0.0 < next_id.0.0);
+    }
 }
@@ -0,0 +1,175 @@
+//! A WASM call-stack depth limiter.
+//!
+//! The LLVM-compiled contract runs on the node's native stack, so unbounded
+//! WASM->WASM recursion grows that stack the same way unbounded native
+//! recursion would: left unchecked, a deeply recursive (or simply buggy)
+//! contract can overflow it and crash the whole node instead of just
+//! failing its own transaction. `wasmer_middlewares::Metering` already
+//! bounds *how much* a contract can run by injecting a point counter at
+//! compile time; this middleware uses the same technique to bound *how
+//! deep* it can call into itself, injecting a depth counter that traps
+//! with `unreachable` (surfaced by the caller as [`ChainError::WasmTrap`])
+//! once [`CallDepthLimit::max_depth`] is exceeded, well before the native
+//! stack would actually give out.
+
+use std::fmt;
+use std::sync::Mutex;
+
+use wasmer::{
+    sys::{FunctionMiddleware, MiddlewareError, MiddlewareReaderState, ModuleMiddleware},
+    wasmparser::{BlockType as WpTypeOrFuncType, Operator},
+    ExportIndex, GlobalInit, GlobalType, LocalFunctionIndex, ModuleInfo, Mutability, Type,
+};
+
+/// Matches EOSIO's default maximum WASM call-stack depth.
+pub const DEFAULT_MAX_CALL_DEPTH: u32 = 250;
+
+/// The module-level call-depth-limiting middleware.
+///
+/// # Panic
+///
+/// An instance of `CallDepthLimit` should _not_ be shared among different
+/// modules, since it tracks module-specific information like the global
+/// index used to store the depth counter.
+pub struct CallDepthLimit {
+    max_depth: u32,
+    depth_global_index: Mutex<Option<u32>>,
+}
+
+impl CallDepthLimit {
+    pub fn new(max_depth: u32) -> Self {
+        Self {
+            max_depth,
+            depth_global_index: Mutex::new(None),
+        }
+    }
+}
+
+impl fmt::Debug for CallDepthLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallDepthLimit")
+            .field("max_depth", &self.max_depth)
+            .field("depth_global_index", &self.depth_global_index)
+            .finish()
+    }
+}
+
+impl ModuleMiddleware for CallDepthLimit {
+    fn generate_function_middleware<'a>(
+        &self,
+        _: LocalFunctionIndex,
+    ) -> Box<dyn FunctionMiddleware<'a> + 'a> {
+        Box::new(FunctionCallDepthLimit {
+            max_depth: self.max_depth,
+            depth_global_index: self
+                .depth_global_index
+                .lock()
+                .unwrap()
+                .expect("CallDepthLimit::transform_module_info must run before function bodies are processed"),
+        })
+    }
+
+    fn transform_module_info(&self, module_info: &mut ModuleInfo) -> Result<(), MiddlewareError> {
+        let mut depth_global_index = self.depth_global_index.lock().unwrap();
+
+        if depth_global_index.is_some() {
+            panic!(
+                "CallDepthLimit::transform_module_info: attempting to use a `CallDepthLimit` middleware from multiple modules."
+            );
+        }
+
+        let global_index = module_info
+            .globals
+            .push(GlobalType::new(Type::I32, Mutability::Var));
+        module_info
+            .global_initializers
+            .push(GlobalInit::I32Const(0));
+        module_info.exports.insert(
+            "pulsevm_call_depth".to_string(),
+            ExportIndex::Global(global_index),
+        );
+
+        *depth_global_index = Some(global_index.as_u32());
+
+        Ok(())
+    }
+}
+
+/// The function-level call-depth-limiting middleware.
+struct FunctionCallDepthLimit {
+    max_depth: u32,
+    depth_global_index: u32,
+}
+
+impl fmt::Debug for FunctionCallDepthLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FunctionCallDepthLimit")
+            .field("max_depth", &self.max_depth)
+            .field("depth_global_index", &self.depth_global_index)
+            .finish()
+    }
+}
+
+impl<'a> FunctionMiddleware<'a> for FunctionCallDepthLimit {
+    fn feed(
+        &mut self,
+        operator: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        // Only `call`/`call_indirect` actually push a new native call frame,
+        // so the counter is incremented and checked right before one and
+        // decremented right after, instead of at function entry/exit. A
+        // `call` always returns control to the instruction right after
+        // itself once the callee is done, no matter which of the callee's
+        // own opcodes (`return`, falling off the closing `end`, or a `br`
+        // that unwinds out to the function scope) it left through, so this
+        // sidesteps having to track every way a function body can exit.
+        let is_call = matches!(
+            operator,
+            Operator::Call { .. } | Operator::CallIndirect { .. }
+        );
+
+        if is_call {
+            // globals[depth] += 1; if unsigned(globals[depth]) > max_depth { throw(); }
+            state.extend(&[
+                Operator::GlobalGet {
+                    global_index: self.depth_global_index,
+                },
+                Operator::I32Const { value: 1 },
+                Operator::I32Add,
+                Operator::GlobalSet {
+                    global_index: self.depth_global_index,
+                },
+                Operator::GlobalGet {
+                    global_index: self.depth_global_index,
+                },
+                Operator::I32Const {
+                    value: self.max_depth as i32,
+                },
+                Operator::I32GtU,
+                Operator::If {
+                    blockty: WpTypeOrFuncType::Empty,
+                },
+                Operator::Unreachable,
+                Operator::End,
+            ]);
+        }
+
+        state.push_operator(operator);
+
+        if is_call {
+            state.extend(&[
+                Operator::GlobalGet {
+                    global_index: self.depth_global_index,
+                },
+                Operator::I32Const { value: 1 },
+                Operator::I32Sub,
+                Operator::GlobalSet {
+                    global_index: self.depth_global_index,
+                },
+            ]);
+        }
+
+        Ok(())
+    }
+}
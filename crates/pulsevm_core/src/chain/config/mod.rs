@@ -1,5 +1,5 @@
 mod node_config;
-pub use node_config::NodeConfig;
+pub use node_config::{default_max_block_time_drift_ms, NodeConfig};
 
 use crate::name::Name;
 use pulsevm_constants::PERCENT_100;
@@ -16,9 +16,16 @@ pub const UPDATEAUTH_NAME: Name = Name::new(name!("updateauth"));
 pub const DELETEAUTH_NAME: Name = Name::new(name!("deleteauth"));
 pub const LINKAUTH_NAME: Name = Name::new(name!("linkauth"));
 pub const UNLINKAUTH_NAME: Name = Name::new(name!("unlinkauth"));
+pub const SETRAM_NAME: Name = Name::new(name!("setram"));
+pub const SETPRIV_NAME: Name = Name::new(name!("setpriv"));
+pub const SETPRODS_NAME: Name = Name::new(name!("setprods"));
 pub const ONERROR_NAME: Name = Name::new(name!("onerror"));
 pub const ONBLOCK_NAME: Name = Name::new(name!("onblock"));
 
+/// Table name under which the `pulse` account stages a not-yet-active
+/// producer schedule proposed by [`crate::chain::pulse_contract::setprods`].
+pub const PRODSCHED_NAME: Name = Name::new(name!("prodsched"));
+
 pub const fn eos_percent(value: u64, percentage: u32) -> u64 {
     (value * percentage as u64) / PERCENT_100
 }
@@ -1,3 +1,4 @@
+use pulsevm_ffi::BlockTimestamp;
 use pulsevm_name::Name;
 use serde::Deserialize;
 
@@ -12,8 +13,30 @@ pub struct NodeConfig {
     // Size of the memory mapped database in bytes
     #[serde(default = "default_db_size")]
     pub db_size: u64,
+    // Number of most-recent blocks to keep in the block log; older blocks
+    // are pruned on every accept. 0 (the default) keeps every block ever
+    // produced, matching the historical behavior.
+    #[serde(default)]
+    pub retained_blocks: u32,
+    // Extra accounts to create at genesis, alongside `pulse` and the other
+    // bios accounts, each owned by genesis's `initial_key`. Lets test and
+    // staging deployments stand up accounts like `pulse.token` without
+    // hand-crafting a `newaccount` transaction after the node comes up.
+    #[serde(default)]
+    pub bootstrap_accounts: Vec<Name>,
+    // How far ahead of this node's own wall clock a block's timestamp is
+    // allowed to be before `verify_block` rejects it outright. This is a
+    // per-node tolerance for clock skew between producers, not a consensus
+    // parameter, so it lives here rather than in the on-chain chain config.
+    #[serde(default = "default_max_block_time_drift_ms")]
+    pub max_block_time_drift_ms: u32,
 }
 
 fn default_db_size() -> u64 {
     20 * 1024 * 1024 * 1024 // 20 GB
 }
+
+pub(crate) fn default_max_block_time_drift_ms() -> u32 {
+    // A handful of block intervals' worth of slack for ordinary NTP drift.
+    BlockTimestamp::BLOCK_INTERVAL_MS as u32 * 10
+}
@@ -2,7 +2,7 @@ use std::{fmt, str::FromStr};
 
 use cxx::SharedPtr;
 use pulsevm_error::ChainError;
-use pulsevm_ffi::{CxxPrivateKey, sign_digest_with_private_key};
+use pulsevm_ffi::{sign_digest_with_private_key, CxxPrivateKey};
 use serde::Deserialize;
 
 use crate::{
@@ -49,6 +49,17 @@ impl FromStr for PrivateKey {
     type Err = ChainError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The FFI layer only understands the checksummed `PVT_K1_` format;
+        // convert a legacy WIF key before handing off to it.
+        let converted = if !s.starts_with("PVT_") {
+            Some(pulsevm_crypto::legacy_wif_to_pvt_k1(s).map_err(|e| {
+                ChainError::TransactionError(format!("invalid legacy private key: {}", e))
+            })?)
+        } else {
+            None
+        };
+        let s = converted.as_deref().unwrap_or(s);
+
         let cxx_key = pulsevm_ffi::parse_private_key(s)
             .map_err(|e| ChainError::TransactionError(e.to_string()))?;
         Ok(PrivateKey { inner: cxx_key })
@@ -78,3 +89,30 @@ impl<'de> Deserialize<'de> for PrivateKey {
         PrivateKey::from_str(&s).map_err(serde::de::Error::custom)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::crypto::PrivateKey;
+
+    #[test]
+    fn test_private_key_parses_legacy_wif_to_the_same_key_as_pvt_k1() {
+        let legacy =
+            PrivateKey::from_str("5Ke22Wm3Y3zH695QcUjMzzs8mRhXoAxUkh2xMeMLoVLR8CFuVEL").unwrap();
+        let k1 = PrivateKey::from_str("PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT")
+            .unwrap();
+        assert_eq!(legacy.get_public_key(), k1.get_public_key());
+        assert_eq!(
+            legacy.to_string(),
+            "PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT"
+        );
+    }
+
+    #[test]
+    fn test_private_key_rejects_a_legacy_wif_with_a_bad_checksum() {
+        assert!(
+            PrivateKey::from_str("5Ke221m3Y3zH695QcUjMzzs8mRhXoAxUkh2xMeMLoVLR8CFuVEL").is_err()
+        );
+    }
+}
@@ -124,6 +124,19 @@ impl FromStr for PublicKey {
     type Err = ChainError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The FFI layer only understands the checksummed `PUB_K1_`/`PUB_R1_`
+        // format; convert the legacy `EOS`-prefixed one `cleos` still
+        // accepts before handing off to it.
+        let converted =
+            if s.starts_with("EOS") {
+                Some(pulsevm_crypto::legacy_public_key_to_pub_k1(s).map_err(|e| {
+                    ChainError::ParseError(format!("invalid legacy public key: {}", e))
+                })?)
+            } else {
+                None
+            };
+        let s = converted.as_deref().unwrap_or(s);
+
         let cxx_key =
             pulsevm_ffi::parse_public_key(s).map_err(|e| ChainError::ParseError(e.to_string()))?;
         Ok(PublicKey { inner: cxx_key })
@@ -163,6 +176,26 @@ mod tests {
         assert!(public_key_1 != public_key_2);
     }
 
+    #[test]
+    fn test_public_key_parses_legacy_eos_format_to_the_same_key_as_pub_k1() {
+        let legacy =
+            PublicKey::from_str("EOS5bbkxaLdB5bfVZW6DJY8M74vwT2m61PqwywNUa5azfkJVh3ijL").unwrap();
+        let k1 = PublicKey::from_str("PUB_K1_5bbkxaLdB5bfVZW6DJY8M74vwT2m61PqwywNUa5azfkJTvYa5H")
+            .unwrap();
+        assert_eq!(legacy, k1);
+        assert_eq!(
+            legacy.to_string(),
+            "PUB_K1_5bbkxaLdB5bfVZW6DJY8M74vwT2m61PqwywNUa5azfkJTvYa5H"
+        );
+    }
+
+    #[test]
+    fn test_public_key_rejects_a_legacy_key_with_a_bad_checksum() {
+        assert!(
+            PublicKey::from_str("EOS5bb1xaLdB5bfVZW6DJY8M74vwT2m61PqwywNUa5azfkJVh3ijL").is_err()
+        );
+    }
+
     #[test]
     fn test_public_key_equals() {
         let key_1_str = "PUB_K1_5bbkxaLdB5bfVZW6DJY8M74vwT2m61PqwywNUa5azfkJTvYa5H";
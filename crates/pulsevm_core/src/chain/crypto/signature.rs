@@ -7,7 +7,7 @@ use std::{
 use cxx::SharedPtr;
 use pulsevm_crypto::FixedBytes;
 use pulsevm_error::ChainError;
-use pulsevm_ffi::{CxxSignature, recover_public_key_from_signature};
+use pulsevm_ffi::{recover_public_key_from_signature, CxxSignature};
 use pulsevm_serialization::{NumBytes, Read, ReadError, Write, WriteError};
 use serde::{Deserialize, Serialize};
 
@@ -148,3 +148,38 @@ impl FromStr for Signature {
         Ok(Signature { inner: cxx_sig })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        crypto::{PublicKey, Signature},
+        utils::Digest,
+    };
+
+    // SIG_HIGH is SIG_CANON with `s` negated modulo the secp256k1 curve order
+    // and the recovery id's parity bit flipped, so both recover the same
+    // point mathematically -- only the canonical one should be accepted.
+    const DIGEST: &str = "d293c2675350402234cdb4306086fe877ffadd1a28e33300b4c5be74afd2db80";
+    const SIG_CANON: &str = "SIG_K1_KUt8HPSrzgk6YxKraRupYdHDXhiAXEdQTL5c6awcfaJW7simkUQSPPVBmxevQDi5aWLYdGJoW7oQfWuwUm7YQZGKoATpKB";
+    const SIG_HIGH: &str = "SIG_K1_JuPaty2pxJJGzACwPohRT4pjYwaMDiAMq4d7vgPBS2RUwoM62CTpqYoUqK1SDQ1oDM1jESUDEdGDoAaZJY4JwM3AUK4aCJ";
+    const PUB_KEY: &str = "PUB_K1_7RnPpendpxNyyC4UkUWKKV8DLVjBxFZRDRgXEiVhAi8PytbX6q";
+
+    fn digest() -> Digest {
+        Digest::from_data(&hex::decode(DIGEST).unwrap())
+    }
+
+    #[test]
+    fn test_recover_public_key_accepts_a_canonical_signature() {
+        let signature = Signature::from_str(SIG_CANON).unwrap();
+        let expected = PublicKey::from_str(PUB_KEY).unwrap();
+        assert_eq!(signature.recover_public_key(&digest()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_recover_public_key_rejects_a_high_s_signature() {
+        let signature = Signature::from_str(SIG_HIGH).unwrap();
+        assert!(signature.recover_public_key(&digest()).is_err());
+    }
+}
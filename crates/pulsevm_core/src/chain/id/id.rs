@@ -7,6 +7,7 @@ use pulsevm_error::ChainError;
 use pulsevm_ffi::CxxDigest;
 use pulsevm_proc_macros::{NumBytes, Read, Write};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as ShaDigest, Sha256};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Read, Write, NumBytes)]
 pub struct Id(pub FixedBytes<32>);
@@ -28,9 +29,52 @@ impl Id {
         Id(FixedBytes::default())
     }
 
+    /// Packs `block_num` into `hash`'s first 4 bytes, big-endian, the way
+    /// EOSIO block ids embed their own height so a peer can derive it from
+    /// the id alone without a lookup.
+    pub fn with_block_num(block_num: u32, mut hash: [u8; 32]) -> Self {
+        hash[0..4].copy_from_slice(&block_num.to_be_bytes());
+        Id(FixedBytes(hash))
+    }
+
+    /// The block height packed into this id's first 4 bytes by
+    /// [`Id::with_block_num`].
+    pub fn block_num(&self) -> u32 {
+        u32::from_be_bytes(self.0.0[0..4].try_into().unwrap())
+    }
+
     pub fn to_digest(&self) -> Result<UniquePtr<CxxDigest>, ChainError> {
         CxxDigest::new_from_existing_hash(self.as_bytes())
     }
+
+    /// Encodes this id the Avalanche/CB58 way: base58 over the raw bytes
+    /// with a 4-byte SHA256 checksum appended, for gRPC-facing clients that
+    /// expect ids in that form rather than hex.
+    pub fn to_cb58(&self) -> String {
+        let mut data = self.0.0.to_vec();
+        let checksum = Sha256::digest(&self.0.0);
+        data.extend_from_slice(&checksum[checksum.len() - 4..]);
+        bs58::encode(data).into_string()
+    }
+
+    /// Inverse of [`Id::to_cb58`].
+    pub fn from_cb58(s: &str) -> Result<Self, IdParseError> {
+        let decoded = bs58::decode(s).into_vec().map_err(|_| IdParseError)?;
+
+        if decoded.len() != 36 {
+            return Err(IdParseError);
+        }
+
+        let (raw, checksum) = decoded.split_at(32);
+        let expected = Sha256::digest(raw);
+        if &expected[expected.len() - 4..] != checksum {
+            return Err(IdParseError);
+        }
+
+        let mut array = [0u8; 32];
+        array.copy_from_slice(raw);
+        Ok(Id(FixedBytes(array)))
+    }
 }
 
 #[derive(Debug)]
@@ -38,7 +82,7 @@ pub struct IdParseError;
 
 impl fmt::Display for IdParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "invalid hex string for id")
+        write!(f, "invalid hex or CB58 string for id")
     }
 }
 
@@ -151,4 +195,41 @@ mod tests {
             .unwrap();
         assert_eq!(id, id2);
     }
+
+    #[test]
+    fn test_id_with_block_num_round_trips_through_block_num() {
+        for block_num in [0u32, 1, 42, 1_000_000, u32::MAX] {
+            let id = Id::with_block_num(block_num, [0xAB; 32]);
+            assert_eq!(id.block_num(), block_num);
+        }
+    }
+
+    #[test]
+    fn test_id_with_block_num_leaves_the_rest_of_the_hash_untouched() {
+        let hash = [0xCDu8; 32];
+        let id = Id::with_block_num(7, hash);
+        assert_eq!(&id.as_bytes()[4..], &hash[4..]);
+    }
+
+    #[test]
+    fn test_id_round_trips_through_hex() {
+        let id = Id::new([0x42; 32]);
+        let round_tripped = Id::from_str(&id.to_string()).unwrap();
+        assert_eq!(id, round_tripped);
+    }
+
+    #[test]
+    fn test_id_round_trips_through_cb58() {
+        let id = Id::new([0x42; 32]);
+        let round_tripped = Id::from_cb58(&id.to_cb58()).unwrap();
+        assert_eq!(id, round_tripped);
+    }
+
+    #[test]
+    fn test_id_from_cb58_rejects_a_corrupted_checksum() {
+        let id = Id::new([0x42; 32]);
+        let mut cb58 = id.to_cb58();
+        cb58.push('z');
+        assert!(Id::from_cb58(&cb58).is_err());
+    }
 }
@@ -1,4 +1,7 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashSet},
+};
 
 use crate::chain::{id::Id, transaction::PackedTransaction};
 
@@ -15,9 +18,48 @@ impl std::fmt::Display for MempoolError {
     }
 }
 
+/// Default priority for transactions submitted without an explicit one.
+/// Since all of them tie, they still come out in FIFO order relative to
+/// each other, same as the old plain-`VecDeque` mempool.
+pub const DEFAULT_PRIORITY: u32 = 0;
+
+/// A transaction sitting in the mempool, ordered by `priority` (higher
+/// first) and, for equal priority, by `sequence` (lower/earlier first) so
+/// same-priority transactions still drain FIFO.
+struct MempoolEntry {
+    priority: u32,
+    sequence: u64,
+    transaction: PackedTransaction,
+}
+
+impl PartialEq for MempoolEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.sequence == other.sequence
+    }
+}
+
+impl Eq for MempoolEntry {}
+
+impl Ord for MempoolEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            // BinaryHeap is a max-heap: to make the earlier sequence number
+            // win on a priority tie, it must compare as the larger value.
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for MempoolEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 pub struct Mempool {
-    transactions_list: VecDeque<PackedTransaction>,
+    entries: BinaryHeap<MempoolEntry>,
     transactions_map: HashSet<Id>,
+    next_sequence: u64,
 }
 
 pub const MAX_MEMPOOL_SIZE: usize = 10000;
@@ -25,48 +67,184 @@ pub const MAX_MEMPOOL_SIZE: usize = 10000;
 impl Mempool {
     pub fn new() -> Self {
         Self {
-            transactions_list: VecDeque::new(),
+            entries: BinaryHeap::new(),
             transactions_map: HashSet::new(),
+            next_sequence: 0,
         }
     }
 
     pub fn add_transaction(&mut self, transaction: PackedTransaction) -> bool {
-        if self.transactions_list.len() >= MAX_MEMPOOL_SIZE {
+        self.add_transaction_with_priority(transaction, DEFAULT_PRIORITY)
+    }
+
+    pub fn add_transaction_with_priority(
+        &mut self,
+        transaction: PackedTransaction,
+        priority: u32,
+    ) -> bool {
+        if self.entries.len() >= MAX_MEMPOOL_SIZE {
             return false; // mempool is full
         }
         if !self.transactions_map.insert(transaction.id().clone()) {
             return false; // already present
         }
-        self.transactions_list.push_back(transaction);
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.entries.push(MempoolEntry {
+            priority,
+            sequence,
+            transaction,
+        });
         true
     }
 
     pub fn pop_transaction(&mut self) -> Option<PackedTransaction> {
-        if let Some(transaction) = self.transactions_list.pop_front() {
-            self.transactions_map.remove(transaction.id());
-            return Some(transaction);
+        if let Some(entry) = self.entries.pop() {
+            self.transactions_map.remove(entry.transaction.id());
+            return Some(entry.transaction);
         }
 
         return None;
     }
 
     pub fn remove_transaction(&mut self, tx_id: &Id) {
-        if let Some(index) = self.transactions_list.iter().position(|x| x.id() == tx_id) {
-            self.transactions_list.remove(index);
-            self.transactions_map.remove(tx_id);
+        let had = self.transactions_map.remove(tx_id);
+        if had {
+            self.entries.retain(|entry| entry.transaction.id() != tx_id);
         }
     }
 
     pub fn has_transactions(&self) -> bool {
-        self.transactions_list.len() > 0
+        self.entries.len() > 0
+    }
+
+    /// Number of transactions currently sitting in the mempool, exposed as
+    /// a gauge through the `gather` (Prometheus) endpoint.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
     }
 
     // Prune transactions that are included in a new block or expired
     pub fn prune(&mut self, pending_ids: &HashSet<Id>) {
-        self.transactions_list
-            .retain(|tx| !pending_ids.contains(tx.id()));
+        self.entries
+            .retain(|entry| !pending_ids.contains(entry.transaction.id()));
         for tx_id in pending_ids {
             self.transactions_map.remove(tx_id);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, str::FromStr};
+
+    use pulsevm_ffi::TimePointSec;
+
+    use super::*;
+    use crate::chain::{
+        crypto::PrivateKey,
+        transaction::{SignedTransaction, Transaction, TransactionCompression, TransactionHeader},
+    };
+
+    fn signed_trx() -> SignedTransaction {
+        signed_trx_with_ref_block(1)
+    }
+
+    // Varying `ref_block_num` gives each transaction a distinct id, so tests
+    // that need several *different* transactions in the mempool at once
+    // don't collide with the id-based dedupe check.
+    fn signed_trx_with_ref_block(ref_block_num: u16) -> SignedTransaction {
+        let private_key =
+            PrivateKey::from_str("PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT")
+                .unwrap();
+        let chain_id =
+            Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
+                .unwrap();
+        let trx = SignedTransaction::new(
+            Transaction::new(
+                TransactionHeader::new(TimePointSec::new(100), ref_block_num, 2, 4.into(), 3, 5.into()),
+                vec![],
+                vec![],
+            ),
+            BTreeSet::new(),
+            vec![],
+        );
+        trx.sign(&private_key, &chain_id).unwrap()
+    }
+
+    #[test]
+    fn test_dedupes_same_transaction_across_compression_modes() {
+        let trx = signed_trx();
+        let uncompressed =
+            PackedTransaction::from_signed_transaction_with_compression(
+                trx.clone(),
+                TransactionCompression::None,
+            )
+            .unwrap();
+        let compressed =
+            PackedTransaction::from_signed_transaction_with_compression(
+                trx,
+                TransactionCompression::Zlib,
+            )
+            .unwrap();
+
+        let mut mempool = Mempool::new();
+        assert!(mempool.add_transaction(uncompressed));
+        // Same transaction, just packed with zlib: must be rejected as a duplicate.
+        assert!(!mempool.add_transaction(compressed));
+        assert_eq!(mempool.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_higher_priority_transaction_pops_before_earlier_lower_priority_one() {
+        let low_priority = PackedTransaction::from_signed_transaction(signed_trx_with_ref_block(1))
+            .unwrap();
+        let high_priority =
+            PackedTransaction::from_signed_transaction(signed_trx_with_ref_block(2)).unwrap();
+
+        let mut mempool = Mempool::new();
+        // Submitted first, but at the default (lowest) priority.
+        assert!(mempool.add_transaction(low_priority.clone()));
+        // Submitted second, but explicitly prioritized above it.
+        assert!(mempool.add_transaction_with_priority(high_priority.clone(), 10));
+
+        assert_eq!(mempool.pop_transaction().unwrap().id(), high_priority.id());
+        assert_eq!(mempool.pop_transaction().unwrap().id(), low_priority.id());
+    }
+
+    #[test]
+    fn test_len_tracks_mempool_depth() {
+        let mut mempool = Mempool::new();
+        assert_eq!(mempool.len(), 0);
+        assert!(mempool.is_empty());
+
+        mempool.add_transaction(
+            PackedTransaction::from_signed_transaction(signed_trx_with_ref_block(1)).unwrap(),
+        );
+        mempool.add_transaction(
+            PackedTransaction::from_signed_transaction(signed_trx_with_ref_block(2)).unwrap(),
+        );
+
+        assert_eq!(mempool.len(), 2);
+        assert!(!mempool.is_empty());
+    }
+
+    #[test]
+    fn test_equal_priority_transactions_pop_in_fifo_order() {
+        let first =
+            PackedTransaction::from_signed_transaction(signed_trx_with_ref_block(1)).unwrap();
+        let second =
+            PackedTransaction::from_signed_transaction(signed_trx_with_ref_block(2)).unwrap();
+
+        let mut mempool = Mempool::new();
+        assert!(mempool.add_transaction(first.clone()));
+        assert!(mempool.add_transaction(second.clone()));
+
+        assert_eq!(mempool.pop_transaction().unwrap().id(), first.id());
+        assert_eq!(mempool.pop_transaction().unwrap().id(), second.id());
+    }
+}
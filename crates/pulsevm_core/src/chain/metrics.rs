@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use pulsevm_error::ChainError;
+use pulsevm_ffi::Microseconds;
+
+/// A short, stable label for why a transaction was dropped instead of being
+/// included in a block, suitable for use as a metric label (unlike
+/// `ChainError`'s `Display`, which embeds a free-form message).
+pub fn rejection_reason(err: &ChainError) -> &'static str {
+    match err {
+        ChainError::InternalError(_) => "internal_error",
+        ChainError::BlockError(_) => "block_error",
+        ChainError::GenesisError(_) => "genesis_error",
+        ChainError::ParseError(_) => "parse_error",
+        ChainError::AuthorizationError(_) => "authorization_error",
+        ChainError::PermissionNotFound(_, _) => "permission_not_found",
+        ChainError::SignatureRecoverError(_) => "signature_recover_error",
+        ChainError::TransactionError(_) => "transaction_error",
+        ChainError::NetworkError(_) => "network_error",
+        ChainError::WasmRuntimeError(_) => "wasm_runtime_error",
+        ChainError::ApplyError(_) => "apply_error",
+        ChainError::DatabaseError(_) => "database_error",
+        ChainError::InvalidArgument(_) => "invalid_argument",
+        ChainError::SerializationError(_) => "serialization_error",
+        ChainError::MissingAuthError(_) => "missing_auth_error",
+        ChainError::ActionValidationError(_) => "action_validation_error",
+        ChainError::IrrelevantAuth(_) => "irrelevant_auth",
+        ChainError::InvalidBlockTimestamp(_) => "invalid_block_timestamp",
+        ChainError::WasmTrap(_) => "wasm_trap",
+        ChainError::TxCpuUsageExceeded { .. } => "tx_cpu_usage_exceeded",
+        ChainError::TxNetUsageExceeded { .. } => "tx_net_usage_exceeded",
+        ChainError::BlockCpuUsageExceeded { .. } => "block_cpu_usage_exceeded",
+        ChainError::BlockNetUsageExceeded { .. } => "block_net_usage_exceeded",
+        ChainError::RamUsageExceeded { .. } => "ram_usage_exceeded",
+        ChainError::ChainIdMismatch { .. } => "chain_id_mismatch",
+    }
+}
+
+/// Cumulative counters describing what the controller and mempool have
+/// done since the process started, surfaced through the gRPC `gather`
+/// (Prometheus) endpoint. `blocks_produced` and `transactions_applied`
+/// only grow, matching the semantics a Prometheus counter is expected to
+/// have; `average_apply_time_us` is derived on read rather than stored.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    blocks_produced: u64,
+    transactions_applied: u64,
+    apply_time_us_total: u64,
+    transactions_rejected: HashMap<String, u64>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Called once a block is genuinely committed (`Controller::accept_block`),
+    /// not when it's merely built or verified, both of which roll back.
+    pub fn record_block_produced(&mut self) {
+        self.blocks_produced += 1;
+    }
+
+    /// Called once a transaction's effects are committed, with the wall-clock
+    /// time `execute_transaction` took to apply it.
+    pub fn record_transaction_applied(&mut self, apply_time: Microseconds) {
+        self.transactions_applied += 1;
+        self.apply_time_us_total += apply_time.count().max(0) as u64;
+    }
+
+    /// Called when a transaction is dropped instead of being included in a
+    /// block, keyed by a short reason (e.g. the `ChainError` variant name).
+    pub fn record_transaction_rejected(&mut self, reason: &str) {
+        *self
+            .transactions_rejected
+            .entry(reason.to_string())
+            .or_insert(0) += 1;
+    }
+
+    pub fn blocks_produced(&self) -> u64 {
+        self.blocks_produced
+    }
+
+    pub fn transactions_applied(&self) -> u64 {
+        self.transactions_applied
+    }
+
+    pub fn transactions_rejected(&self) -> &HashMap<String, u64> {
+        &self.transactions_rejected
+    }
+
+    pub fn average_apply_time_us(&self) -> f64 {
+        if self.transactions_applied == 0 {
+            0.0
+        } else {
+            self.apply_time_us_total as f64 / self.transactions_applied as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transactions_applied_counts_each_recorded_transaction() {
+        let mut metrics = Metrics::new();
+        for _ in 0..5 {
+            metrics.record_transaction_applied(Microseconds::new(10));
+        }
+
+        assert_eq!(metrics.transactions_applied(), 5);
+    }
+
+    #[test]
+    fn test_average_apply_time_is_mean_of_recorded_durations() {
+        let mut metrics = Metrics::new();
+        metrics.record_transaction_applied(Microseconds::new(10));
+        metrics.record_transaction_applied(Microseconds::new(30));
+
+        assert_eq!(metrics.average_apply_time_us(), 20.0);
+    }
+
+    #[test]
+    fn test_rejection_reason_is_stable_across_error_messages() {
+        assert_eq!(
+            rejection_reason(&ChainError::TransactionError("expired".into())),
+            rejection_reason(&ChainError::TransactionError("duplicate".into())),
+        );
+        assert_eq!(
+            rejection_reason(&ChainError::TransactionError("expired".into())),
+            "transaction_error"
+        );
+    }
+
+    #[test]
+    fn test_transactions_rejected_grouped_by_reason() {
+        let mut metrics = Metrics::new();
+        metrics.record_transaction_rejected("TransactionError");
+        metrics.record_transaction_rejected("TransactionError");
+        metrics.record_transaction_rejected("AuthorizationError");
+
+        assert_eq!(metrics.transactions_rejected().get("TransactionError"), Some(&2));
+        assert_eq!(metrics.transactions_rejected().get("AuthorizationError"), Some(&1));
+    }
+}
@@ -5,15 +5,20 @@ pub mod asset;
 pub mod authority_checker;
 pub mod authorization_manager;
 pub mod block;
+mod call_depth_limit;
 pub mod config;
 pub mod controller;
 pub mod crypto;
 pub mod id;
 pub mod mempool;
+pub mod metrics;
+pub mod producer_schedule;
 pub mod pulse_contract;
 pub mod resource;
 pub mod resource_limits;
 pub mod state_history;
+pub mod state_summary;
+pub mod table;
 pub mod transaction;
 pub mod transaction_context;
 pub mod utils;
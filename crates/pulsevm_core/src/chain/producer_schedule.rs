@@ -0,0 +1,36 @@
+use pulsevm_proc_macros::{NumBytes, Read, Write};
+use serde::Serialize;
+
+use crate::chain::{crypto::PublicKey, name::Name, table::ChainbaseObject};
+
+/// A single producer's block-signing key, keyed by account name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Read, Write, NumBytes, Serialize)]
+pub struct ProducerKey {
+    pub producer_name: Name,
+    pub block_signing_key: PublicKey,
+}
+
+/// The set of producers allowed to sign blocks, and the version at which
+/// that set took effect. `setprods` stores its schedule at the previous
+/// stored version plus one on every call, even if the producer set is
+/// unchanged; `Controller::get_producer_schedule` always reports version 1.
+#[derive(Clone, Debug, PartialEq, Eq, Read, Write, NumBytes, Serialize)]
+pub struct ProducerSchedule {
+    pub version: u32,
+    pub producers: Vec<ProducerKey>,
+}
+
+impl ProducerSchedule {
+    pub fn new(version: u32, producers: Vec<ProducerKey>) -> Self {
+        Self { version, producers }
+    }
+}
+
+/// A singleton row, so a proposed schedule can be staged under the `pulse`
+/// account's own table the same way contract state is, without needing a
+/// new field on the C++ global property object.
+impl ChainbaseObject for ProducerSchedule {
+    fn primary_key(&self) -> u64 {
+        0
+    }
+}
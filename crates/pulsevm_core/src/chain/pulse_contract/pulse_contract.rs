@@ -1,20 +1,26 @@
+use std::collections::HashSet;
+
 use pulsevm_billable_size::billable_size_v;
 use pulsevm_constants::{OVERHEAD_PER_ACCOUNT_RAM_BYTES, SETCODE_RAM_BYTES_MULTIPLIER};
 use pulsevm_error::ChainError;
-use pulsevm_ffi::{CxxDigest, Database, PermissionObject};
-use pulsevm_serialization::Read;
+use pulsevm_ffi::{CxxDigest, Database, KeyValueIteratorCache, KeyValueObject, PermissionObject};
+use pulsevm_serialization::{NumBytes, Read};
 
 use crate::{
-    ACTIVE_NAME, CODE_NAME, OWNER_NAME,
+    ACTIVE_NAME, CODE_NAME, OWNER_NAME, PULSE_NAME,
     chain::{
         abi::AbiDefinition,
         apply_context::ApplyContext,
         authority::{Authority, PermissionLevel},
         authorization_manager::AuthorizationManager,
+        config::PRODSCHED_NAME,
+        producer_schedule::ProducerSchedule,
         pulse_contract::pulse_contract_types::{
-            DeleteAuth, LinkAuth, NewAccount, SetAbi, SetCode, UnlinkAuth, UpdateAuth,
+            DeleteAuth, LinkAuth, NewAccount, SetAbi, SetCodeRef, SetPriv, SetProds, SetRam,
+            UnlinkAuth, UpdateAuth,
         },
         resource_limits::ResourceLimitsManager,
+        table::Table,
         utils::pulse_assert,
     },
     transaction::Action,
@@ -101,12 +107,15 @@ pub fn newaccount(
 
     ResourceLimitsManager::initialize_account(db, &create.name)?;
 
+    // The new account doesn't have any RAM of its own yet, so its creator
+    // pays for the account object and both permission objects, and fails if
+    // they don't have the quota to cover it.
     let mut ram_delta: i64 = OVERHEAD_PER_ACCOUNT_RAM_BYTES as i64;
     ram_delta += 2 * billable_size_v::<PermissionObject>() as i64;
     ram_delta += owner_permission.get_authority().get_billable_size() as i64;
     ram_delta += active_permission.get_authority().get_billable_size() as i64;
 
-    context.add_ram_usage(&create.name, ram_delta)?;
+    context.add_ram_usage(&create.creator, ram_delta)?;
 
     Ok(())
 }
@@ -117,7 +126,7 @@ pub fn setcode(
     act: &Action,
 ) -> Result<(), ChainError> {
     let act = act
-        .data_as::<SetCode>()
+        .data_as_ref::<SetCodeRef>()
         .map_err(|e| ChainError::TransactionError(format!("failed to deserialize data: {}", e)))?;
     context.require_authorization(&act.account, None)?;
 
@@ -410,6 +419,144 @@ pub fn unlinkauth(
     Ok(())
 }
 
+pub fn setram(
+    context: &mut ApplyContext,
+    db: &mut Database,
+    act: &Action,
+) -> Result<(), ChainError> {
+    let set = act
+        .data_as::<SetRam>()
+        .map_err(|e| ChainError::TransactionError(format!("failed to deserialize data: {}", e)))?;
+    context.require_authorization(&PULSE_NAME, None)?;
+
+    pulse_assert(
+        set.bytes > 0,
+        ChainError::TransactionError("bytes to add must be positive".to_string()),
+    )?;
+
+    let mut ram_bytes = 0i64;
+    let mut net_weight = 0i64;
+    let mut cpu_weight = 0i64;
+    ResourceLimitsManager::get_account_limits(
+        db,
+        &set.account,
+        &mut ram_bytes,
+        &mut net_weight,
+        &mut cpu_weight,
+    )?;
+
+    ResourceLimitsManager::set_account_limits(
+        db,
+        &set.account,
+        net_weight,
+        cpu_weight,
+        ram_bytes + set.bytes,
+    )?;
+
+    Ok(())
+}
+
+pub fn setpriv(
+    context: &mut ApplyContext,
+    db: &mut Database,
+    act: &Action,
+) -> Result<(), ChainError> {
+    let set = act
+        .data_as::<SetPriv>()
+        .map_err(|e| ChainError::TransactionError(format!("failed to deserialize data: {}", e)))?;
+    context.require_authorization(&PULSE_NAME, None)?;
+
+    let account = db.find_account_metadata(set.account.as_u64())?;
+    pulse_assert(
+        !account.is_null(),
+        ChainError::TransactionError(format!("account {} does not exist", set.account)),
+    )?;
+
+    db.set_privileged(set.account.as_u64(), set.is_priv)?;
+
+    Ok(())
+}
+
+/// Stages a proposed producer schedule under the `pulse` account's own
+/// table, the same way contract state is stored, rather than promoting it
+/// immediately: there's no multi-producer block production yet to promote
+/// it into.
+pub fn setprods(
+    context: &mut ApplyContext,
+    db: &mut Database,
+    act: &Action,
+) -> Result<(), ChainError> {
+    let set = act
+        .data_as::<SetProds>()
+        .map_err(|e| ChainError::TransactionError(format!("failed to deserialize data: {}", e)))?;
+    context.require_authorization(&PULSE_NAME, None)?;
+
+    pulse_assert(
+        !set.schedule.is_empty(),
+        ChainError::TransactionError("proposed producer schedule cannot be empty".to_string()),
+    )?;
+
+    let mut seen = HashSet::new();
+    for producer in &set.schedule {
+        pulse_assert(
+            !producer.producer_name.empty(),
+            ChainError::TransactionError("producer name cannot be empty".to_string()),
+        )?;
+        pulse_assert(
+            seen.insert(producer.producer_name),
+            ChainError::TransactionError(format!(
+                "duplicate producer {} in proposed schedule",
+                producer.producer_name
+            )),
+        )?;
+    }
+
+    let mut keyval_cache = KeyValueIteratorCache::new();
+    let mut table = proposed_schedule_table(db, &mut keyval_cache);
+
+    let existing = table.find(0)?;
+    let next_version = existing.as_ref().map_or(1, |s| s.version + 1);
+    let proposed = ProducerSchedule::new(next_version, set.schedule.clone());
+
+    let overhead = billable_size_v::<KeyValueObject>() as i64;
+    let old_size = existing.map_or(0, |s| s.num_bytes() as i64 + overhead);
+    let new_size = proposed.num_bytes() as i64 + overhead;
+
+    if old_size > 0 {
+        table.modify(PULSE_NAME.as_u64(), &proposed)?;
+    } else {
+        table.emplace(PULSE_NAME.as_u64(), &proposed)?;
+    }
+
+    if new_size != old_size {
+        context.add_ram_usage(&PULSE_NAME, new_size - old_size)?;
+    }
+
+    Ok(())
+}
+
+fn proposed_schedule_table<'a>(
+    db: &'a mut Database,
+    keyval_cache: &'a mut KeyValueIteratorCache,
+) -> Table<'a, ProducerSchedule> {
+    Table::new(
+        db,
+        keyval_cache,
+        PULSE_NAME.as_u64(),
+        PULSE_NAME.as_u64(),
+        PRODSCHED_NAME.as_u64(),
+    )
+}
+
+/// The schedule most recently staged by [`setprods`], if any. Nothing
+/// promotes it to the active schedule yet.
+pub fn get_proposed_producer_schedule(
+    db: &mut Database,
+) -> Result<Option<ProducerSchedule>, ChainError> {
+    let mut keyval_cache = KeyValueIteratorCache::new();
+    proposed_schedule_table(db, &mut keyval_cache).find(0)
+}
+
 fn validate_authority_precondition(db: &mut Database, auth: &Authority) -> Result<(), ChainError> {
     for a in auth.accounts() {
         let _ = db.get_account(a.permission.actor).map_err(|_| {
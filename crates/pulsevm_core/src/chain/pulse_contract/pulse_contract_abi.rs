@@ -2,7 +2,7 @@ use crate::chain::{
     abi::{AbiActionDefinition, AbiDefinition, AbiStructDefinition, AbiTypeDefinition},
     config::{
         DELETEAUTH_NAME, LINKAUTH_NAME, NEWACCOUNT_NAME, ONBLOCK_NAME, ONERROR_NAME, SETABI_NAME,
-        SETCODE_NAME, UNLINKAUTH_NAME, UPDATEAUTH_NAME,
+        SETCODE_NAME, SETPRIV_NAME, SETPRODS_NAME, SETRAM_NAME, UNLINKAUTH_NAME, UPDATEAUTH_NAME,
     },
 };
 
@@ -195,6 +195,27 @@ pub fn get_pulse_contract_abi() -> AbiDefinition {
                     ("type".to_owned(), "action_name".to_owned()).into(),
                 ],
             },
+            AbiStructDefinition {
+                name: "setram".to_string(),
+                base: "".to_string(),
+                fields: vec![
+                    ("account".to_owned(), "account_name".to_owned()).into(),
+                    ("bytes".to_owned(), "int64".to_owned()).into(),
+                ],
+            },
+            AbiStructDefinition {
+                name: "setpriv".to_string(),
+                base: "".to_string(),
+                fields: vec![
+                    ("account".to_owned(), "account_name".to_owned()).into(),
+                    ("is_priv".to_owned(), "bool".to_owned()).into(),
+                ],
+            },
+            AbiStructDefinition {
+                name: "setprods".to_string(),
+                base: "".to_string(),
+                fields: vec![("schedule".to_owned(), "producer_key[]".to_owned()).into()],
+            },
             AbiStructDefinition {
                 name: "onerror".to_string(),
                 base: "".to_string(),
@@ -245,6 +266,21 @@ pub fn get_pulse_contract_abi() -> AbiDefinition {
                 type_name: "unlinkauth".to_string(),
                 ricardian_contract: "".to_string(),
             },
+            AbiActionDefinition {
+                name: SETRAM_NAME.into(),
+                type_name: "setram".to_string(),
+                ricardian_contract: "".to_string(),
+            },
+            AbiActionDefinition {
+                name: SETPRIV_NAME.into(),
+                type_name: "setpriv".to_string(),
+                ricardian_contract: "".to_string(),
+            },
+            AbiActionDefinition {
+                name: SETPRODS_NAME.into(),
+                type_name: "setprods".to_string(),
+                ricardian_contract: "".to_string(),
+            },
             AbiActionDefinition {
                 name: ONERROR_NAME.into(),
                 type_name: "onerror".to_string(),
@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
-use pulsevm_crypto::Bytes;
+use pulsevm_crypto::{Bytes, BytesRef};
 use pulsevm_proc_macros::{NumBytes, Read, Write};
-use pulsevm_serialization::Write;
+use pulsevm_serialization::{Read, ReadError, ReadRef, Write};
 
-use crate::chain::{authority::Authority, name::Name};
+use crate::chain::{authority::Authority, name::Name, producer_schedule::ProducerKey};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
 pub struct NewAccount {
@@ -67,6 +67,35 @@ impl TryFrom<SetCode> for Arc<[u8]> {
     }
 }
 
+/// The zero-copy counterpart to [`SetCode`]: `code` borrows straight out of
+/// the [`crate::chain::transaction::Action`]'s own data buffer instead of
+/// being copied into an owned [`Bytes`]. `setcode` only needs to hash and
+/// validate the WASM, not keep it around past the call, so there's no reason
+/// to pay for a second allocation of a blob that can already be a few
+/// hundred KB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetCodeRef<'a> {
+    pub account: Name,
+    pub vm_type: u8,
+    pub vm_version: u8,
+    pub code: BytesRef<'a>,
+}
+
+impl<'a> ReadRef<'a> for SetCodeRef<'a> {
+    fn read_ref(bytes: &'a [u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let account = Name::read(bytes, pos)?;
+        let vm_type = u8::read(bytes, pos)?;
+        let vm_version = u8::read(bytes, pos)?;
+        let code = BytesRef::read_ref(bytes, pos)?;
+        Ok(SetCodeRef {
+            account,
+            vm_type,
+            vm_version,
+            code,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
 pub struct SetAbi {
     pub account: Name,
@@ -81,6 +110,55 @@ impl TryFrom<SetAbi> for Arc<[u8]> {
     }
 }
 
+/// Testing affordance that grants an account more RAM quota without going
+/// through a full system contract: callable only by `pulse`. Increases
+/// `account`'s RAM limit in [`crate::chain::resource_limits::ResourceLimitsManager`]
+/// by `bytes`, leaving its net/CPU weights untouched.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
+pub struct SetRam {
+    pub account: Name,
+    pub bytes: i64,
+}
+
+/// Sets or clears `account`'s privileged flag in `AccountMetadata`:
+/// callable only by `pulse`. Privileged accounts can call the privileged
+/// WASM intrinsics (`set_blockchain_parameters_packed`, `set_privileged`,
+/// etc.) and create `pulse.`-prefixed accounts; this is how `pulse` grants
+/// that to a newly deployed system contract.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
+pub struct SetPriv {
+    pub account: Name,
+    pub is_priv: bool,
+}
+
+/// Proposes a new producer schedule: callable only by `pulse`. Staged as
+/// the pending schedule rather than applied immediately, since there's no
+/// multi-producer block production or schedule promotion yet.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
+pub struct SetProds {
+    pub schedule: Vec<ProducerKey>,
+}
+
+/// Payload of the `onerror` action a deferred transaction's sender receives
+/// when that transaction fails, carrying the id the sender originally
+/// scheduled it under and the packed transaction itself so the handler can
+/// inspect what was attempted. Matches EOSIO's `onerror` action data; nothing
+/// in this tree schedules deferred transactions yet, so nothing constructs
+/// this outside of tests.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
+pub struct OnError {
+    pub sender_id: u128,
+    pub sent_trx: Bytes,
+}
+
+impl TryFrom<OnError> for Arc<[u8]> {
+    type Error = String;
+
+    fn try_from(value: OnError) -> Result<Self, Self::Error> {
+        value.pack().map(Arc::from).map_err(|e| e.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -126,4 +204,17 @@ mod tests {
 
         assert_eq!(new_account, unpacked);
     }
+
+    #[test]
+    fn test_on_error_serialization() {
+        let on_error = OnError {
+            sender_id: u128::MAX - 1,
+            sent_trx: Bytes::from(vec![1, 2, 3, 4]),
+        };
+
+        let packed = on_error.pack().unwrap();
+        let unpacked = OnError::read(&packed, &mut 0).unwrap();
+
+        assert_eq!(on_error, unpacked);
+    }
 }
@@ -44,14 +44,68 @@ impl ResourceLimitsManager {
     ) -> Result<(), ChainError> {
         db.add_transaction_usage(account, cpu_usage, net_usage, time_slot)
             .map_err(|e| {
-                ChainError::DatabaseError(format!(
-                    "failed to add transaction usage for account {}: {}",
-                    account, e
-                ))
+                Self::classify_transaction_usage_error(db, account, cpu_usage, net_usage, &e)
             })?;
         Ok(())
     }
 
+    /// Tells apart the four distinct ways [`Database::add_transaction_usage`]
+    /// can fail to exhaust a resource (account CPU/net window, block
+    /// CPU/net capacity) by matching the literal message text the
+    /// `EOS_ASSERT`s it's backed by are declared with, since the underlying
+    /// C++ exception type doesn't cross the FFI boundary. Anything else
+    /// falls back to a plain [`ChainError::DatabaseError`].
+    fn classify_transaction_usage_error(
+        db: &Database,
+        account: &Name,
+        cpu_usage: u64,
+        net_usage: u64,
+        err: &ChainError,
+    ) -> ChainError {
+        let message = err.to_string();
+
+        if message.contains("insufficient objective cpu resources") {
+            let limit = Self::get_account_cpu_limit(db, account, None)
+                .map(|(limit, _)| limit.max(0) as u64)
+                .unwrap_or(0);
+            return ChainError::TxCpuUsageExceeded {
+                used: cpu_usage,
+                limit,
+            };
+        }
+
+        if message.contains("insufficient net resources for this transaction") {
+            let limit = Self::get_account_net_limit(db, account, None)
+                .map(|(limit, _)| limit.max(0) as u64)
+                .unwrap_or(0);
+            return ChainError::TxNetUsageExceeded {
+                used: net_usage,
+                limit,
+            };
+        }
+
+        if message.contains("Block has insufficient cpu resources") {
+            let limit = db.get_block_cpu_limit().unwrap_or(0);
+            return ChainError::BlockCpuUsageExceeded {
+                used: cpu_usage,
+                limit,
+            };
+        }
+
+        if message.contains("Block has insufficient net resources") {
+            let limit = db.get_block_net_limit().unwrap_or(0);
+            return ChainError::BlockNetUsageExceeded {
+                used: net_usage,
+                limit,
+            };
+        }
+
+        ChainError::DatabaseError(format!(
+            "failed to add transaction usage for account {}: {}",
+            account, message
+        ))
+    }
+
     pub fn add_pending_ram_usage(
         db: &mut Database,
         account: &Name,
@@ -72,15 +126,46 @@ impl ResourceLimitsManager {
         account_name: &Name,
     ) -> Result<(), ChainError> {
         db.verify_account_ram_usage(account_name.as_u64())
-            .map_err(|e| {
-                ChainError::DatabaseError(format!(
-                    "failed to verify ram usage for account {}: {}",
-                    account_name, e
-                ))
-            })?;
+            .map_err(|e| Self::classify_ram_usage_error(db, account_name, &e))?;
         Ok(())
     }
 
+    /// Matches the literal message text `verify_account_ram_usage`'s
+    /// `EOS_ASSERT` is declared with, for the same reason
+    /// [`Self::classify_transaction_usage_error`] does.
+    fn classify_ram_usage_error(
+        db: &Database,
+        account_name: &Name,
+        err: &ChainError,
+    ) -> ChainError {
+        let message = err.to_string();
+
+        if message.contains("insufficient ram") {
+            let usage = Self::get_account_ram_usage(db, account_name).unwrap_or(0);
+            let mut ram_bytes = 0i64;
+            let mut net_weight = 0i64;
+            let mut cpu_weight = 0i64;
+            let _ = Self::get_account_limits(
+                db,
+                account_name,
+                &mut ram_bytes,
+                &mut net_weight,
+                &mut cpu_weight,
+            );
+
+            return ChainError::RamUsageExceeded {
+                account: account_name.to_string(),
+                usage,
+                limit: ram_bytes,
+            };
+        }
+
+        ChainError::DatabaseError(format!(
+            "failed to verify ram usage for account {}: {}",
+            account_name, message
+        ))
+    }
+
     pub fn get_account_ram_usage(db: &Database, account: &Name) -> Result<i64, ChainError> {
         match db.get_account_ram_usage(account.as_u64()) {
             Ok(usage) => Ok(usage),
@@ -159,6 +244,46 @@ impl ResourceLimitsManager {
         Ok((res.limit, res.greylisted))
     }
 
+    /// Net usage for `account` in the current rate-limiting window, as
+    /// `(used, available, max)`. All three are `-1` when the account has
+    /// no net weight assigned (i.e. it is unlimited for this resource).
+    pub fn get_account_net_usage(
+        db: &Database,
+        account: &Name,
+        greylist_limit: Option<u32>,
+    ) -> Result<(i64, i64, i64), ChainError> {
+        let res = db
+            .get_account_net_usage(account.as_u64(), greylist_limit.unwrap_or(1000))
+            .map_err(|e| {
+                ChainError::DatabaseError(format!(
+                    "failed to get net usage for account {}: {}",
+                    account, e
+                ))
+            })?;
+
+        Ok((res.used, res.available, res.max))
+    }
+
+    /// Cpu usage for `account` in the current rate-limiting window, as
+    /// `(used, available, max)`. All three are `-1` when the account has
+    /// no cpu weight assigned (i.e. it is unlimited for this resource).
+    pub fn get_account_cpu_usage(
+        db: &Database,
+        account: &Name,
+        greylist_limit: Option<u32>,
+    ) -> Result<(i64, i64, i64), ChainError> {
+        let res = db
+            .get_account_cpu_usage(account.as_u64(), greylist_limit.unwrap_or(1000))
+            .map_err(|e| {
+                ChainError::DatabaseError(format!(
+                    "failed to get cpu usage for account {}: {}",
+                    account, e
+                ))
+            })?;
+
+        Ok((res.used, res.available, res.max))
+    }
+
     pub fn process_account_limit_updates(db: &mut Database) -> Result<(), ChainError> {
         db.process_account_limit_updates().map_err(|e| {
             ChainError::DatabaseError(format!("failed to process account limit updates: {}", e))
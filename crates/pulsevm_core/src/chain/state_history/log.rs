@@ -105,7 +105,7 @@ const IDX_RECORD_SIZE: u64 = 12;
 /// Extract EOS block number from a block id (first 4 bytes big-endian)
 #[inline]
 fn num_from_block_id(id: &Id) -> u32 {
-    u32::from_be_bytes(id.0.0[0..4].try_into().unwrap())
+    id.block_num()
 }
 
 /// Validate a header at `pos` against the known total file length and
@@ -701,9 +701,7 @@ mod tests {
     }
 
     fn make_id(block_num: u32, filler: u8) -> Id {
-        let mut b = [filler; 32];
-        b[0..4].copy_from_slice(&block_num.to_be_bytes());
-        Id(FixedBytes(b))
+        Id::with_block_num(block_num, [filler; 32])
     }
 
     /// Independent, minimal parser for the on-disk format. This is the
@@ -0,0 +1,74 @@
+use pulsevm_crypto::Digest;
+use pulsevm_error::ChainError;
+use pulsevm_proc_macros::{NumBytes, Read, Write};
+use pulsevm_serialization::{NumBytes, Read, Write};
+
+use crate::chain::id::Id;
+
+/// A summary of the chain's state at a given height, used by Avalanche's
+/// state sync handshake (`GetLastStateSummary` / `ParseStateSummary`).
+///
+/// `id` is a content hash of the state at `height`, so that two nodes with
+/// identical state at the same height agree on the summary id without
+/// exchanging the underlying snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Read, Write, NumBytes)]
+pub struct StateSummary {
+    pub id: Id,
+    pub height: u64,
+}
+
+impl StateSummary {
+    pub fn new(id: Id, height: u64) -> Self {
+        Self { id, height }
+    }
+
+    /// Builds the summary for a chain with head `height` and head block id
+    /// `head_id`. The summary id is a hash of both so it changes whenever
+    /// either the head block or the height it was produced at changes.
+    pub fn for_head(head_id: Id, height: u64) -> Self {
+        let mut bytes = head_id.as_bytes().to_vec();
+        bytes.extend_from_slice(&height.to_le_bytes());
+        let hash = Digest::hash(&bytes);
+        Self::new(Id::new(*hash.as_bytes()), height)
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, ChainError> {
+        Self::read(bytes, &mut 0)
+            .map_err(|e| ChainError::ParseError(format!("failed to parse state summary: {}", e)))
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ChainError> {
+        self.pack()
+            .map_err(|e| ChainError::ParseError(format!("failed to pack state summary: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_head_is_stable_for_unchanged_state() {
+        let head_id = Id::new([7u8; 32]);
+        let a = StateSummary::for_head(head_id, 42);
+        let b = StateSummary::for_head(head_id, 42);
+        assert_eq!(a, b);
+        assert_eq!(a.height, 42);
+    }
+
+    #[test]
+    fn test_for_head_changes_with_height() {
+        let head_id = Id::new([7u8; 32]);
+        let a = StateSummary::for_head(head_id, 42);
+        let b = StateSummary::for_head(head_id, 43);
+        assert_ne!(a.id, b.id);
+    }
+
+    #[test]
+    fn test_roundtrip_bytes() {
+        let summary = StateSummary::for_head(Id::new([9u8; 32]), 100);
+        let bytes = summary.to_bytes().unwrap();
+        let parsed = StateSummary::parse(&bytes).unwrap();
+        assert_eq!(summary, parsed);
+    }
+}
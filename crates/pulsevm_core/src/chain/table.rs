@@ -0,0 +1,295 @@
+use pulsevm_error::ChainError;
+use pulsevm_ffi::{Database, KeyValueIteratorCache, TableObject};
+use pulsevm_serialization::{NumBytes, Read, Write};
+
+use crate::chain::utils::pulse_assert;
+
+/// A row type that can be stored in a generic key/value [`Table`], keyed by a
+/// u64 primary key. This is the Rust-side analog of an EOSIO
+/// `multi_index`-compatible row type.
+pub trait ChainbaseObject: Read + Write + NumBytes + Sized {
+    fn primary_key(&self) -> u64;
+}
+
+/// Ergonomic wrapper over `KeyValueIteratorCache`/`TableObject`/`KeyValueObject`
+/// for a single `(code, scope, table)` triple, so Rust-side code doesn't have
+/// to juggle raw iterator handles and byte buffers directly, the way WASM
+/// host functions in `ApplyContext` do.
+///
+/// RAM billing is left to the caller, unlike `ApplyContext`'s own `db_*`
+/// methods, which bill the payer through `update_db_usage`: only the caller
+/// knows which payer/usage-tracking policy applies to the table in question.
+pub struct Table<'a, T: ChainbaseObject> {
+    db: &'a mut Database,
+    keyval_cache: &'a mut KeyValueIteratorCache,
+    code: u64,
+    scope: u64,
+    table: u64,
+    _row: std::marker::PhantomData<T>,
+}
+
+impl<'a, T: ChainbaseObject> Table<'a, T> {
+    pub fn new(
+        db: &'a mut Database,
+        keyval_cache: &'a mut KeyValueIteratorCache,
+        code: u64,
+        scope: u64,
+        table: u64,
+    ) -> Self {
+        Self {
+            db,
+            keyval_cache,
+            code,
+            scope,
+            table,
+            _row: std::marker::PhantomData,
+        }
+    }
+
+    fn find_or_create_table(&mut self, payer: u64) -> Result<*const TableObject, ChainError> {
+        let existing = self.db.find_table(self.code, self.scope, self.table)?;
+        if !existing.is_null() {
+            return Ok(existing);
+        }
+        self.db.create_table(self.code, self.scope, self.table, payer)
+    }
+
+    pub fn emplace(&mut self, payer: u64, row: &T) -> Result<(), ChainError> {
+        let table = self.find_or_create_table(payer)?;
+        let table = unsafe { &*table };
+
+        let data = row.pack().map_err(|e| {
+            ChainError::SerializationError(format!("failed to pack table row: {}", e))
+        })?;
+        let obj = self
+            .db
+            .create_key_value_object(table, payer, row.primary_key(), &data)?;
+        let obj = unsafe { &*obj };
+        self.keyval_cache.cache_table(table)?;
+        self.keyval_cache.add(obj)?;
+        Ok(())
+    }
+
+    pub fn modify(&mut self, payer: u64, row: &T) -> Result<(), ChainError> {
+        let iterator = self.db.db_find_i64(
+            self.code,
+            self.scope,
+            self.table,
+            row.primary_key(),
+            self.keyval_cache,
+        )?;
+        pulse_assert(
+            iterator >= 0,
+            ChainError::DatabaseError("table row not found".into()),
+        )?;
+
+        let obj = self.keyval_cache.get(iterator)?;
+        let data = row.pack().map_err(|e| {
+            ChainError::SerializationError(format!("failed to pack table row: {}", e))
+        })?;
+        self.db.update_key_value_object(obj, payer, &data)
+    }
+
+    pub fn erase(&mut self, primary_key: u64) -> Result<(), ChainError> {
+        let iterator =
+            self.db
+                .db_find_i64(self.code, self.scope, self.table, primary_key, self.keyval_cache)?;
+        pulse_assert(
+            iterator >= 0,
+            ChainError::DatabaseError("table row not found".into()),
+        )?;
+
+        self.db
+            .db_remove_i64(self.keyval_cache, iterator, self.code)?;
+        Ok(())
+    }
+
+    pub fn find(&mut self, primary_key: u64) -> Result<Option<T>, ChainError> {
+        let iterator =
+            self.db
+                .db_find_i64(self.code, self.scope, self.table, primary_key, self.keyval_cache)?;
+        if iterator < 0 {
+            return Ok(None);
+        }
+        self.decode(iterator).map(Some)
+    }
+
+    fn decode(&self, iterator: i32) -> Result<T, ChainError> {
+        let obj = self.keyval_cache.get(iterator)?;
+        T::read(obj.get_value().as_slice(), &mut 0).map_err(|e| {
+            ChainError::SerializationError(format!("failed to unpack table row: {}", e))
+        })
+    }
+
+    fn end_iterator(&mut self) -> Result<i32, ChainError> {
+        self.db
+            .db_end_i64(self.keyval_cache, self.code, self.scope, self.table)
+    }
+
+    /// A forward iterator over every row in this table, from the lowest
+    /// primary key to the highest, stopping at the end iterator like
+    /// `multi_index::end()`.
+    ///
+    /// Ordering matches EOSIO: the underlying `by_scope_primary` index
+    /// compares primary keys as raw `u64` (not `i64`), so e.g. `u64::MAX`
+    /// sorts after `0` rather than before it as a negative number would.
+    /// This is deterministic across nodes since it depends only on the
+    /// primary key values, never insertion order.
+    pub fn iter(&mut self) -> Result<TableIter<'_, 'a, T>, ChainError> {
+        let end = self.end_iterator()?;
+        let current =
+            self.db
+                .db_lowerbound_i64(self.keyval_cache, self.code, self.scope, self.table, 0)?;
+        Ok(TableIter {
+            table: self,
+            current,
+            end,
+        })
+    }
+}
+
+pub struct TableIter<'t, 'a, T: ChainbaseObject> {
+    table: &'t mut Table<'a, T>,
+    current: i32,
+    end: i32,
+}
+
+impl<'t, 'a, T: ChainbaseObject> Iterator for TableIter<'t, 'a, T> {
+    type Item = Result<T, ChainError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current == self.end {
+            return None;
+        }
+
+        let row = self.table.decode(self.current);
+        let mut primary = 0u64;
+        match self
+            .table
+            .db
+            .db_next_i64(self.table.keyval_cache, self.current, &mut primary)
+        {
+            Ok(next) => self.current = next,
+            Err(e) => return Some(Err(e)),
+        }
+        Some(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pulsevm_ffi::{Database, KeyValueIteratorCache};
+    use pulsevm_proc_macros::{NumBytes, Read, Write};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes)]
+    struct Row {
+        id: u64,
+        value: u64,
+    }
+
+    impl ChainbaseObject for Row {
+        fn primary_key(&self) -> u64 {
+            self.id
+        }
+    }
+
+    fn open_temp_db() -> (tempfile::TempDir, Database) {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let mut db = Database::new(dir.path().to_str().unwrap(), 1024 * 1024 * 16)
+            .expect("failed to open database");
+        db.add_indices().unwrap();
+        (dir, db)
+    }
+
+    #[test]
+    fn test_emplace_and_find() {
+        let (_dir, mut db) = open_temp_db();
+        let mut cache = KeyValueIteratorCache::new();
+        let mut table: Table<Row> = Table::new(&mut db, &mut cache, 1, 2, 3);
+
+        table.emplace(1, &Row { id: 1, value: 100 }).unwrap();
+        table.emplace(1, &Row { id: 2, value: 200 }).unwrap();
+
+        assert_eq!(table.find(1).unwrap(), Some(Row { id: 1, value: 100 }));
+        assert_eq!(table.find(2).unwrap(), Some(Row { id: 2, value: 200 }));
+        assert_eq!(table.find(3).unwrap(), None);
+    }
+
+    #[test]
+    fn test_modify_and_erase() {
+        let (_dir, mut db) = open_temp_db();
+        let mut cache = KeyValueIteratorCache::new();
+        let mut table: Table<Row> = Table::new(&mut db, &mut cache, 1, 2, 3);
+
+        table.emplace(1, &Row { id: 1, value: 100 }).unwrap();
+        table.modify(1, &Row { id: 1, value: 101 }).unwrap();
+        assert_eq!(table.find(1).unwrap(), Some(Row { id: 1, value: 101 }));
+
+        table.erase(1).unwrap();
+        assert_eq!(table.find(1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_iterates_all_rows_and_stops_at_end_iterator() {
+        let (_dir, mut db) = open_temp_db();
+        let mut cache = KeyValueIteratorCache::new();
+        let mut table: Table<Row> = Table::new(&mut db, &mut cache, 1, 2, 3);
+
+        table.emplace(1, &Row { id: 1, value: 100 }).unwrap();
+        table.emplace(1, &Row { id: 2, value: 200 }).unwrap();
+        table.emplace(1, &Row { id: 3, value: 300 }).unwrap();
+
+        let rows: Vec<Row> = table.iter().unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(
+            rows,
+            vec![
+                Row { id: 1, value: 100 },
+                Row { id: 2, value: 200 },
+                Row { id: 3, value: 300 },
+            ]
+        );
+
+        // Calling iter() again must yield the same rows and terminate cleanly
+        // (the end iterator is stable, it doesn't advance with the cursor).
+        assert_eq!(table.iter().unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_empty_table_iterator_is_immediately_at_end() {
+        let (_dir, mut db) = open_temp_db();
+        let mut cache = KeyValueIteratorCache::new();
+        let mut table: Table<Row> = Table::new(&mut db, &mut cache, 1, 2, 3);
+
+        assert_eq!(table.iter().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_iteration_order_is_ascending_by_raw_u64_primary_key_regardless_of_insertion_order() {
+        let (_dir, mut db) = open_temp_db();
+        let mut cache = KeyValueIteratorCache::new();
+        let mut table: Table<Row> = Table::new(&mut db, &mut cache, 1, 2, 3);
+
+        // Insert out of primary-key order, including a key whose top bit is
+        // set: if iteration compared keys as signed i64, this row would sort
+        // *before* 0 instead of after u64::MAX - 1.
+        table
+            .emplace(1, &Row { id: 5, value: 500 })
+            .unwrap();
+        table
+            .emplace(1, &Row { id: u64::MAX, value: 999 })
+            .unwrap();
+        table.emplace(1, &Row { id: 0, value: 0 }).unwrap();
+        table
+            .emplace(1, &Row { id: u64::MAX - 1, value: 998 })
+            .unwrap();
+        table.emplace(1, &Row { id: 2, value: 200 }).unwrap();
+
+        let ids: Vec<u64> = table.iter().unwrap().map(|r| r.unwrap().id).collect();
+        assert_eq!(ids, vec![0, 2, 5, u64::MAX - 1, u64::MAX]);
+
+        // The end iterator must be reached exactly once, not loop or stop short.
+        assert_eq!(table.iter().unwrap().count(), ids.len());
+    }
+}
@@ -6,7 +6,7 @@ use pulsevm_serialization::{NumBytes, Read, Write};
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
 
-use crate::chain::{Name, authority::PermissionLevel};
+use crate::chain::{authority::PermissionLevel, Name};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, Default)]
 pub struct Action {
@@ -63,6 +63,17 @@ impl Action {
         T::read(&self.data, &mut pos)
     }
 
+    /// Like [`Action::data_as`], but for a `T` that borrows straight out of
+    /// this action's own data buffer (see [`pulsevm_serialization::ReadRef`])
+    /// instead of copying it, for callers on a hot path that don't need an
+    /// owned value.
+    pub fn data_as_ref<'a, T: pulsevm_serialization::ReadRef<'a>>(
+        &'a self,
+    ) -> Result<T, pulsevm_serialization::ReadError> {
+        let mut pos = 0;
+        T::read_ref(&self.data, &mut pos)
+    }
+
     pub fn digest(&self) -> [u8; 32] {
         let bytes: Vec<u8> = self.pack().unwrap();
         sha2::Sha256::digest(&bytes).into()
@@ -132,3 +143,19 @@ mod arc_bytes_serde {
         Ok(Arc::from(bytes))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ACTIVE_NAME;
+
+    #[test]
+    fn test_action_digest_covers_the_return_value() {
+        let action = Action::new(ACTIVE_NAME, ACTIVE_NAME, vec![1, 2, 3], vec![]);
+
+        let digest_a = generate_action_digest(&action, Some(vec![4, 5, 6]));
+        let digest_b = generate_action_digest(&action, Some(vec![7, 8, 9]));
+
+        assert_ne!(digest_a, digest_b);
+    }
+}
@@ -1,13 +1,13 @@
 use std::{collections::BTreeMap, fmt};
 
-use pulsevm_crypto::Digest;
+use pulsevm_crypto::{hash_packed, Digest};
 use pulsevm_error::ChainError;
 use pulsevm_proc_macros::{NumBytes, Read, Write};
-use pulsevm_serialization::Write;
+use serde::Serialize;
 
 use crate::chain::name::Name;
 
-#[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes)]
+#[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes, Serialize)]
 pub struct ActionReceipt {
     pub receiver: Name,
     pub act_digest: Digest,
@@ -44,11 +44,7 @@ impl ActionReceipt {
     }
 
     pub fn digest(&self) -> Result<Digest, ChainError> {
-        let packed = self
-            .pack()
-            .map_err(|e| ChainError::SerializationError(e.to_string()))?;
-
-        Ok(Digest::hash(&packed))
+        hash_packed(self).map_err(|e| ChainError::SerializationError(e.to_string()))
     }
 }
 
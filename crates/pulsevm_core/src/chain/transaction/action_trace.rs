@@ -3,6 +3,7 @@ use std::collections::BTreeMap;
 
 use pulsevm_ffi::BlockTimestamp;
 use pulsevm_proc_macros::{NumBytes, Read, Write};
+use serde::Serialize;
 
 use crate::chain::{
     id::Id,
@@ -10,7 +11,7 @@ use crate::chain::{
     transaction::{Action, ActionReceipt},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes)]
+#[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes, Serialize)]
 pub struct ActionTrace {
     pub action_ordinal: u32,
     pub creator_action_ordinal: u32,
@@ -20,6 +21,8 @@ pub struct ActionTrace {
     pub act: Action,
     pub context_free: bool,
     pub elapsed: u32,
+    pub cpu_usage_us: u32,
+    pub net_usage_bytes: u32,
     pub console: String,
     pub trx_id: Id,
     pub block_num: u32,
@@ -55,6 +58,8 @@ impl ActionTrace {
             context_free,
             receipt: None,
             elapsed: 0,
+            cpu_usage_us: 0,
+            net_usage_bytes: 0,
             console: String::new(),
             account_ram_deltas,
             except: None,
@@ -87,6 +92,26 @@ impl ActionTrace {
         self.elapsed = elapsed;
     }
 
+    /// CPU microseconds this action alone was billed, as opposed to
+    /// [`Self::elapsed`] which is the wall-clock time spent applying it.
+    pub fn cpu_usage_us(&self) -> u32 {
+        self.cpu_usage_us
+    }
+
+    pub fn set_cpu_usage_us(&mut self, cpu_usage_us: u32) {
+        self.cpu_usage_us = cpu_usage_us;
+    }
+
+    /// NET bytes this action alone contributed to the transaction's total
+    /// `net_usage`.
+    pub fn net_usage_bytes(&self) -> u32 {
+        self.net_usage_bytes
+    }
+
+    pub fn set_net_usage_bytes(&mut self, net_usage_bytes: u32) {
+        self.net_usage_bytes = net_usage_bytes;
+    }
+
     pub fn context_free(&self) -> bool {
         self.context_free
     }
@@ -96,13 +121,15 @@ impl fmt::Display for ActionTrace {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "action_trace {{ action_ordinal: {}, creator_action_ordinal: {}, receiver: {}, act: {}, context_free: {}, elapsed: {}, console: {}, except: {:?}, error_code: {:?}, return_value: {:?} }}",
+            "action_trace {{ action_ordinal: {}, creator_action_ordinal: {}, receiver: {}, act: {}, context_free: {}, elapsed: {}, cpu_usage_us: {}, net_usage_bytes: {}, console: {}, except: {:?}, error_code: {:?}, return_value: {:?} }}",
             self.action_ordinal,
             self.creator_action_ordinal,
             self.receiver,
             self.act,
             self.context_free,
             self.elapsed,
+            self.cpu_usage_us,
+            self.net_usage_bytes,
             self.console,
             self.except,
             self.error_code,
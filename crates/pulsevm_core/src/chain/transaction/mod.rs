@@ -5,7 +5,7 @@ mod action_trace;
 pub use action_trace::ActionTrace;
 
 mod action;
-pub use action::{Action, generate_action_digest};
+pub use action::{generate_action_digest, Action};
 
 mod packed_transaction;
 pub use packed_transaction::PackedTransaction;
@@ -30,3 +30,6 @@ pub use transaction_trace::TransactionTrace;
 
 mod transaction;
 pub use transaction::Transaction;
+
+mod transaction_builder;
+pub use transaction_builder::TransactionBuilder;
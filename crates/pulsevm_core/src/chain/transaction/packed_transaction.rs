@@ -1,11 +1,14 @@
-use std::{collections::BTreeSet, io::Read as IoRead};
+use std::{
+    collections::BTreeSet,
+    io::{Read as IoRead, Write as IoWrite},
+};
 
-use flate2::read::ZlibDecoder;
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 use pulsevm_constants::FIXED_NET_OVERHEAD_OF_PACKED_TRX;
 use pulsevm_crypto::Bytes;
 use pulsevm_error::ChainError;
 use pulsevm_serialization::{NumBytes, Read, ReadError, Write, WriteError};
-use serde::{Serialize, ser::SerializeStruct};
+use serde::{ser::SerializeStruct, Serialize};
 
 use crate::{
     chain::{
@@ -104,21 +107,33 @@ impl PackedTransaction {
 
     #[inline]
     pub fn from_signed_transaction(trx: SignedTransaction) -> Result<Self, ChainError> {
+        Self::from_signed_transaction_with_compression(trx, TransactionCompression::None)
+    }
+
+    pub fn from_signed_transaction_with_compression(
+        trx: SignedTransaction,
+        compression: TransactionCompression,
+    ) -> Result<Self, ChainError> {
         let trx_id = trx.transaction().id().map_err(|e| {
             ChainError::SerializationError(format!("failed to get transaction ID: {}", e))
         })?;
 
+        let packed_trx_bytes = trx.transaction().pack().map_err(|e| {
+            ChainError::SerializationError(format!("failed to pack transaction: {}", e))
+        })?;
+        let packed_cfd_bytes = if trx.context_free_data().is_empty() {
+            Vec::new()
+        } else {
+            trx.context_free_data().pack().map_err(|e| {
+                ChainError::SerializationError(format!("failed to pack context free data: {}", e))
+            })?
+        };
+
         Ok(Self {
             signatures: trx.signatures().clone(),
-            compression: TransactionCompression::None, // Default to no compression for now
-            packed_context_free_data: Bytes::default(), // No context-free data for now
-            packed_trx: trx
-                .transaction()
-                .pack()
-                .map_err(|e| {
-                    ChainError::SerializationError(format!("failed to pack transaction: {}", e))
-                })?
-                .into(),
+            compression,
+            packed_context_free_data: maybe_compress(compression, &packed_cfd_bytes)?.into(),
+            packed_trx: maybe_compress(compression, &packed_trx_bytes)?.into(),
 
             unpacked_trx: trx,
             trx_id,
@@ -180,6 +195,30 @@ impl Serialize for PackedTransaction {
     }
 }
 
+/// Upper bound on the decompressed size of a single packed transaction or its
+/// context-free data, to stop a malicious peer from submitting a tiny zlib
+/// stream that expands into gigabytes of memory (a "decompression bomb").
+const MAX_DECOMPRESSED_SIZE: u64 = 16 * 1024 * 1024; // 16 MiB
+
+#[inline]
+fn maybe_compress(compression: TransactionCompression, data: &[u8]) -> Result<Vec<u8>, ChainError> {
+    match compression {
+        TransactionCompression::None => Ok(data.to_vec()),
+        TransactionCompression::Zlib => {
+            if data.is_empty() {
+                return Ok(Vec::new());
+            }
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).map_err(|e| {
+                ChainError::SerializationError(format!("zlib compress failed: {e}"))
+            })?;
+            encoder
+                .finish()
+                .map_err(|e| ChainError::SerializationError(format!("zlib compress failed: {e}")))
+        }
+    }
+}
+
 #[inline]
 fn maybe_decompress(
     compression: TransactionCompression,
@@ -191,12 +230,112 @@ fn maybe_decompress(
             if data.is_empty() {
                 return Ok(Vec::new());
             }
-            let mut decoder = ZlibDecoder::new(data);
+            let decoder = ZlibDecoder::new(data);
             let mut out = Vec::new();
-            decoder.read_to_end(&mut out).map_err(|e| {
-                ChainError::SerializationError(format!("zlib decompress failed: {e}"))
-            })?;
+            decoder
+                .take(MAX_DECOMPRESSED_SIZE + 1)
+                .read_to_end(&mut out)
+                .map_err(|e| {
+                    ChainError::SerializationError(format!("zlib decompress failed: {e}"))
+                })?;
+            pulse_assert(
+                out.len() as u64 <= MAX_DECOMPRESSED_SIZE,
+                ChainError::TransactionError(
+                    "decompressed packed transaction exceeds maximum allowed size".into(),
+                ),
+            )?;
             Ok(out)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, str::FromStr};
+
+    use pulsevm_ffi::TimePointSec;
+
+    use super::*;
+    use crate::chain::transaction::{Transaction, TransactionHeader};
+
+    fn signed_trx() -> SignedTransaction {
+        let private_key = crate::crypto::PrivateKey::from_str(
+            "PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT",
+        )
+        .unwrap();
+        let chain_id =
+            Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
+                .unwrap();
+        let trx = SignedTransaction::new(
+            Transaction::new(
+                TransactionHeader::new(TimePointSec::new(100), 1, 2, 4.into(), 3, 5.into()),
+                vec![],
+                vec![],
+            ),
+            BTreeSet::new(),
+            vec![],
+        );
+        trx.sign(&private_key, &chain_id).unwrap()
+    }
+
+    #[test]
+    fn test_from_signed_transaction_roundtrip_none() {
+        let trx = signed_trx();
+        let packed = PackedTransaction::from_signed_transaction_with_compression(
+            trx.clone(),
+            TransactionCompression::None,
+        )
+        .unwrap();
+        assert_eq!(packed.get_signed_transaction(), &trx);
+
+        let bytes = packed.pack().unwrap();
+        let roundtripped = PackedTransaction::read(&bytes, &mut 0).unwrap();
+        assert_eq!(roundtripped.get_signed_transaction(), &trx);
+    }
+
+    #[test]
+    fn test_from_signed_transaction_roundtrip_zlib() {
+        let trx = signed_trx();
+        let packed = PackedTransaction::from_signed_transaction_with_compression(
+            trx.clone(),
+            TransactionCompression::Zlib,
+        )
+        .unwrap();
+        assert_eq!(packed.get_signed_transaction(), &trx);
+
+        let bytes = packed.pack().unwrap();
+        let roundtripped = PackedTransaction::read(&bytes, &mut 0).unwrap();
+        assert_eq!(roundtripped.get_signed_transaction(), &trx);
+    }
+
+    #[test]
+    fn test_maybe_decompress_rejects_oversized_output() {
+        // A zlib stream of all-zero bytes compresses to a tiny payload but
+        // expands far past MAX_DECOMPRESSED_SIZE when inflated.
+        let huge = vec![0u8; (MAX_DECOMPRESSED_SIZE as usize) + 1024];
+        let compressed = maybe_compress(TransactionCompression::Zlib, &huge).unwrap();
+        assert!(compressed.len() < huge.len());
+
+        let err = maybe_decompress(TransactionCompression::Zlib, &compressed).unwrap_err();
+        assert!(matches!(err, ChainError::TransactionError(_)));
+    }
+
+    #[test]
+    fn test_id_is_independent_of_compression() {
+        let trx = signed_trx();
+        let none = PackedTransaction::from_signed_transaction_with_compression(
+            trx.clone(),
+            TransactionCompression::None,
+        )
+        .unwrap();
+        let zlib = PackedTransaction::from_signed_transaction_with_compression(
+            trx,
+            TransactionCompression::Zlib,
+        )
+        .unwrap();
+
+        // Same underlying transaction, different compression: ids must match
+        // so the mempool dedupes them as the same transaction.
+        assert_eq!(none.id(), zlib.id());
+    }
+}
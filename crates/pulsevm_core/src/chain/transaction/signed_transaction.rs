@@ -46,20 +46,30 @@ impl SignedTransaction {
         &self.context_free_data
     }
 
-    #[must_use]
+    /// Computes the signing digest (transaction plus context-free data) and
+    /// recovers the public key behind every signature. This is the single
+    /// entry point the mempool and the controller should use instead of
+    /// duplicating digest/recovery logic.
     #[inline]
-    pub fn recovered_keys(&self, chain_id: &Id) -> Result<BTreeSet<PublicKey>, ChainError> {
-        let mut recovered_keys: BTreeSet<PublicKey> = BTreeSet::new();
+    pub fn verify(&self, chain_id: &Id) -> Result<(Digest, BTreeSet<PublicKey>), ChainError> {
         let digest = self
             .transaction
             .signing_digest(chain_id, &self.context_free_data)?;
         let digest: Digest = Digest::from_data(&digest);
 
+        let mut recovered_keys: BTreeSet<PublicKey> = BTreeSet::new();
         for signature in self.signatures.iter() {
             let public_key = signature.recover_public_key(&digest)?;
             recovered_keys.insert(public_key);
         }
 
+        Ok((digest, recovered_keys))
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn recovered_keys(&self, chain_id: &Id) -> Result<BTreeSet<PublicKey>, ChainError> {
+        let (_, recovered_keys) = self.verify(chain_id)?;
         Ok(recovered_keys)
     }
 
@@ -102,7 +112,10 @@ pub fn signing_digest(
 mod tests {
     use std::{collections::BTreeSet, str::FromStr};
 
+    use pulsevm_crypto::Bytes;
     use pulsevm_ffi::TimePointSec;
+    use pulsevm_serialization::Write;
+    use sha2::Digest as Sha2Digest;
 
     use crate::{
         crypto::PrivateKey,
@@ -143,4 +156,86 @@ mod tests {
         assert_eq!(recovered_keys.len(), 1);
         assert!(recovered_keys.contains(&public_key));
     }
+
+    #[test]
+    fn test_signing_digest_with_context_free_data() {
+        let private_key =
+            PrivateKey::from_str("PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT")
+                .unwrap();
+        let public_key = private_key.get_public_key();
+        let context_free_data = vec![Bytes::new(b"some context free data".to_vec())];
+        let tx = SignedTransaction::new(
+            Transaction::new(
+                TransactionHeader::new(TimePointSec::new(100), 1, 2, 4.into(), 3, 5.into()),
+                vec![],
+                vec![],
+            ),
+            BTreeSet::new(),
+            context_free_data.clone(),
+        );
+        let chain_id =
+            Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
+                .unwrap();
+
+        let signing_digest = tx
+            .transaction
+            .signing_digest(&chain_id, &tx.context_free_data)
+            .unwrap();
+
+        // Reference algorithm per EOSIO: sha256(chain_id || packed_trx ||
+        // sha256(packed_context_free_data)). Reproduced independently here
+        // (rather than reusing signing_digest()) so the test would catch a
+        // regression in either implementation.
+        let packed_trx = tx.transaction.pack().unwrap();
+        let packed_cfd = context_free_data.pack().unwrap();
+        let cf_hash: [u8; 32] = sha2::Sha256::digest(&packed_cfd).into();
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(&chain_id.0);
+        hasher.update(&packed_trx);
+        hasher.update(&cf_hash);
+        let expected: [u8; 32] = hasher.finalize().into();
+
+        assert_eq!(signing_digest, expected);
+
+        let signed_tx = tx.sign(&private_key, &chain_id).unwrap();
+        let recovered_keys = signed_tx.recovered_keys(&chain_id).unwrap();
+        assert_eq!(recovered_keys.len(), 1);
+        assert!(recovered_keys.contains(&public_key));
+    }
+
+    #[test]
+    fn test_verify_recovers_all_signing_keys() {
+        let private_key1 =
+            PrivateKey::from_str("PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT")
+                .unwrap();
+        let private_key2 =
+            PrivateKey::from_str("PVT_K1_5G7JEG7CWZkGfnaQePCcJSNgocGFoeCxG1pU7r1B6rY2gueez")
+                .unwrap();
+        let public_key1 = private_key1.get_public_key();
+        let public_key2 = private_key2.get_public_key();
+
+        let tx = SignedTransaction::new(
+            Transaction::new(
+                TransactionHeader::new(TimePointSec::new(100), 1, 2, 4.into(), 3, 5.into()),
+                vec![],
+                vec![],
+            ),
+            BTreeSet::new(),
+            vec![],
+        );
+        let chain_id =
+            Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
+                .unwrap();
+
+        let signed_tx = tx
+            .sign(&private_key1, &chain_id)
+            .unwrap()
+            .sign(&private_key2, &chain_id)
+            .unwrap();
+
+        let (_digest, recovered_keys) = signed_tx.verify(&chain_id).unwrap();
+        assert_eq!(recovered_keys.len(), 2);
+        assert!(recovered_keys.contains(&public_key1));
+        assert!(recovered_keys.contains(&public_key2));
+    }
 }
@@ -5,13 +5,13 @@ use pulsevm_error::ChainError;
 use pulsevm_ffi::{BlockTimestamp, TimePointSec};
 use pulsevm_proc_macros::{NumBytes, Read, Write};
 use pulsevm_serialization::{VarUint32, Write};
-use serde::{Deserialize, Serialize, ser::SerializeStruct};
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use sha2::Digest;
 
 use crate::{
     chain::{
         id::Id,
-        transaction::{SignedTransaction, TransactionHeader, signed_transaction::signing_digest},
+        transaction::{signed_transaction::signing_digest, SignedTransaction, TransactionHeader},
     },
     crypto::PrivateKey,
     utils::pulse_assert,
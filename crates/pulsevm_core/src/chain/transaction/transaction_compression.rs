@@ -2,8 +2,8 @@ use std::fmt;
 
 use pulsevm_serialization::{NumBytes, Read, ReadError, Write, WriteError};
 use serde::{
-    Deserialize, Deserializer, Serialize,
     de::{self, Visitor},
+    Deserialize, Deserializer, Serialize,
 };
 
 #[repr(u8)]
@@ -1,6 +1,6 @@
-use pulsevm_crypto::Digest;
+use pulsevm_crypto::{hash_packed, Digest};
 use pulsevm_proc_macros::{NumBytes, Read, Write};
-use pulsevm_serialization::{Write, WriteError};
+use pulsevm_serialization::WriteError;
 use serde::Serialize;
 
 use crate::chain::transaction::{PackedTransaction, TransactionReceiptHeader};
@@ -28,6 +28,6 @@ impl TransactionReceipt {
     }
 
     pub fn digest(&self) -> Result<Digest, WriteError> {
-        Ok(Digest::hash(self.pack()?))
+        hash_packed(self)
     }
 }
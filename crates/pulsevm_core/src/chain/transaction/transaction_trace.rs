@@ -1,5 +1,7 @@
+use pulsevm_error::ChainError;
 use pulsevm_ffi::BlockTimestamp;
 use pulsevm_proc_macros::{NumBytes, Read, Write};
+use serde::Serialize;
 
 use crate::chain::{
     account::AccountDelta,
@@ -7,7 +9,12 @@ use crate::chain::{
     transaction::{ActionTrace, TransactionReceiptHeader},
 };
 
-#[derive(Default, Clone, Read, Write, NumBytes)]
+/// Field names and nesting match what `nodeos`'s `get_transaction_trace`
+/// produces, so tooling built against `nodeos` can consume this unmodified.
+/// `except`/`error_code` stay `None` on a successful transaction and are the
+/// only fields a caller that caught a [`ChainError`] needs to fill in -- see
+/// [`TransactionTrace::with_exception`].
+#[derive(Default, Clone, Read, Write, NumBytes, Serialize)]
 pub struct TransactionTrace {
     pub id: Id,
     pub block_num: u32,
@@ -19,7 +26,12 @@ pub struct TransactionTrace {
     pub action_traces: Vec<ActionTrace>,
     pub account_ram_delta: Option<AccountDelta>,
 
-    pub except: Option<u8>,
+    /// The exception message, the same as `nodeos` renders `except` for a
+    /// failed transaction. `nodeos` itself nests a structured
+    /// `code`/`name`/`message`/`stack` object here; this repo only keeps the
+    /// message, matching the simplification the `pulsevm` crate's SHiP wire
+    /// type (`TransactionTraceV0`) already makes for the same field.
+    pub except: Option<String>,
     pub error_code: Option<u64>,
 }
 
@@ -31,4 +43,74 @@ impl TransactionTrace {
     pub fn action_traces(&self) -> &Vec<ActionTrace> {
         &self.action_traces
     }
+
+    /// Embeds a caught [`ChainError`] into this trace's `except` field, the
+    /// way a caller that wants to return a failed transaction as data
+    /// (rather than propagate it as an RPC error) would use it. No code path
+    /// in this crate does that today -- every `Controller::execute_transaction`
+    /// failure short-circuits via `?` before a trace is finalized -- so this
+    /// exists for callers that build or replay traces outside that path.
+    pub fn with_exception(mut self, err: &ChainError) -> Self {
+        self.except = Some(err.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulsevm_serialization::VarUint32;
+
+    use crate::chain::transaction::TransactionStatus;
+
+    #[test]
+    fn test_serialize_matches_the_nodeos_get_transaction_trace_shape() {
+        let trace = TransactionTrace {
+            id: Id::new([0x11; 32]),
+            block_num: 42,
+            block_time: BlockTimestamp::new(0),
+            receipt: TransactionReceiptHeader::new(TransactionStatus::Executed, 500, VarUint32(4)),
+            elapsed: 123,
+            net_usage: 128,
+            scheduled: false,
+            action_traces: vec![],
+            account_ram_delta: None,
+            except: None,
+            error_code: None,
+        };
+
+        let value = serde_json::to_value(&trace).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "id": hex::encode([0x11u8; 32]),
+                "block_num": 42,
+                "block_time": trace.block_time.to_eos_string(),
+                "receipt": {
+                    "status": "executed",
+                    "cpu_usage_us": 500,
+                    "net_usage_words": 4,
+                },
+                "elapsed": 123,
+                "net_usage": 128,
+                "scheduled": false,
+                "action_traces": [],
+                "account_ram_delta": null,
+                "except": null,
+                "error_code": null,
+            })
+        );
+    }
+
+    #[test]
+    fn test_with_exception_sets_except_to_the_error_message() {
+        let trace = TransactionTrace::default()
+            .with_exception(&ChainError::TransactionError("deadline exceeded".into()));
+
+        assert_eq!(
+            trace.except,
+            Some("transaction error: deadline exceeded".to_string())
+        );
+        assert_eq!(trace.error_code, None);
+    }
 }
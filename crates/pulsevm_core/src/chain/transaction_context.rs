@@ -1,15 +1,21 @@
 use std::{
-    collections::{BTreeMap, BTreeSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
     sync::{Arc, RwLock},
 };
 
 use pulsevm_crypto::Digest;
 use pulsevm_error::ChainError;
-use pulsevm_ffi::{BlockTimestamp, Database, Microseconds, TimePoint, seconds};
+use pulsevm_ffi::{
+    seconds, AccountMetadataObject, AccountObject, BlockTimestamp, Database, Microseconds,
+    TimePoint,
+};
 use pulsevm_serialization::VarUint32;
 
 use crate::{
-    authorization_manager::AuthorizationManager, block::BlockStatus, chain::{
+    authorization_manager::AuthorizationManager,
+    block::BlockStatus,
+    chain::{
+        account::AccountDelta,
         apply_context::ApplyContext,
         id::Id,
         name::Name,
@@ -17,7 +23,9 @@ use crate::{
         transaction::{Action, ActionTrace, Transaction, TransactionStatus, TransactionTrace},
         utils::pulse_assert,
         wasm_runtime::WasmRuntime,
-    }, controller::Controller, transaction::PackedTransaction,
+    },
+    controller::Controller,
+    transaction::PackedTransaction,
 };
 
 #[derive(Default, Clone)]
@@ -27,6 +35,25 @@ struct Billing {
     billed_time: Microseconds,
 }
 
+/// Caps the number of table iterators a transaction's actions may open in
+/// total, across every `db_*_store`/`db_idx*_store` host call. Not an
+/// upstream EOSIO limit: `pulsevm_ffi`'s `iterator_cache` never shrinks its
+/// backing vector on `remove` (it just nulls the slot), so a contract that
+/// opens iterators in a loop - even one that closes each one right after -
+/// still grows that vector without bound. Counting every iterator ever
+/// opened, rather than only the currently live ones, matches what actually
+/// costs memory.
+pub const DEFAULT_MAX_OPEN_ITERATORS_PER_TRANSACTION: u32 = 1024;
+
+/// Fallback for [`pulsevm_ffi::CxxChainConfig::get_net_usage_leeway`], mirroring
+/// upstream's own default (`genesis_state.hpp`'s `net_usage_leeway`) in case a
+/// chain config ever reports zero. Extra slack folded into the transaction
+/// and block net-usage limits while a block is still being produced, so a
+/// transaction that rounds up to just past the limit because of word
+/// rounding still gets included - re-validating that same block later
+/// checks against the real limit, with no leeway.
+pub const DEFAULT_NET_USAGE_LEEWAY: u64 = 500;
+
 pub struct TransactionResult {
     pub trace: TransactionTrace,
     pub billed_cpu_time_us: u32,
@@ -44,6 +71,22 @@ struct TransactionContextInner {
     cpu_limit: i64,
     executed_action_receipt_digests: VecDeque<Digest>,
     is_input: bool,
+    // Memoizes `Database::get_account`/`get_account_metadata` lookups for the
+    // life of this transaction. Chainbase modifies rows in place (`modify()`
+    // keeps the same address), so a cached reference already observes any
+    // later modification - no write-back or explicit invalidation is needed,
+    // this purely saves the repeated index lookup for an account that the
+    // same transaction's actions keep reading (authorization checks, RAM
+    // billing, code lookup).
+    account_cache: HashMap<Name, &'static AccountObject>,
+    account_metadata_cache: HashMap<Name, &'static AccountMetadataObject>,
+    open_iterator_count: u32,
+    // Snapshot of the chain config's net-usage limit and leeway, taken once
+    // in `init()` so `add_net_usage` doesn't need to re-read global
+    // properties on every action. Zero (the default before `init()` runs)
+    // disables the corresponding check.
+    max_transaction_net_usage: u64,
+    net_usage_leeway: u64,
 }
 
 #[derive(Clone)]
@@ -89,6 +132,11 @@ impl TransactionContext {
                 cpu_limit: 0,
                 executed_action_receipt_digests: VecDeque::with_capacity(6),
                 is_input: false,
+                account_cache: HashMap::new(),
+                account_metadata_cache: HashMap::new(),
+                open_iterator_count: 0,
+                max_transaction_net_usage: 0,
+                net_usage_leeway: 0,
             })),
             packed_transaction,
         }
@@ -157,7 +205,8 @@ impl TransactionContext {
             discounted_size_for_pruned_data = (discounted_size_for_pruned_data
                 + chain_config.get_context_free_discount_net_usage_den() as u64
                 - 1)
-                / chain_config.get_context_free_discount_net_usage_den() as u64; // rounds up
+                / chain_config.get_context_free_discount_net_usage_den() as u64;
+            // rounds up
         }
 
         let initial_net_usage: u64 = (chain_config.get_base_per_transaction_net_usage() as u64)
@@ -165,6 +214,17 @@ impl TransactionContext {
             + discounted_size_for_pruned_data;
         let first_authorizer = transaction.first_authorizer();
 
+        let net_usage_leeway = chain_config.get_net_usage_leeway() as u64;
+        {
+            let mut inner = self.inner.write()?;
+            inner.max_transaction_net_usage = chain_config.get_max_transaction_net_usage() as u64;
+            inner.net_usage_leeway = if net_usage_leeway > 0 {
+                net_usage_leeway
+            } else {
+                DEFAULT_NET_USAGE_LEEWAY
+            };
+        }
+
         self.validate_expiration(self.packed_transaction.get_transaction())?;
         self.validate_referenced_accounts(self.packed_transaction.get_transaction())?;
         self.init(initial_net_usage, first_authorizer, true)?;
@@ -284,6 +344,15 @@ impl TransactionContext {
             (t.action().clone(), t.receiver().clone(), t.context_free())
         })?;
 
+        spdlog::debug!(
+            "[trx {}] applying action #{} {}::{} -> {}",
+            self.trx_id()?,
+            action_ordinal,
+            action.account(),
+            action.name(),
+            receiver,
+        );
+
         let mut apply_context = ApplyContext::new(
             self.db.clone(),
             self.wasm_runtime.clone(),
@@ -300,6 +369,19 @@ impl TransactionContext {
         let cpu_used = apply_context.exec(self)?;
         self.add_cpu_usage(cpu_used)?;
 
+        // Attribute this action's own payload size to the transaction's NET
+        // usage and record it on the action's trace. The per-action figures
+        // cover only these payload bytes: the transaction-level total also
+        // holds the base and packed-size usage added in `init`, and
+        // `finalize` rounds it up to whole words, so they don't sum to it.
+        let net_used = action.data().len() as u64;
+        self.add_net_usage(net_used)?;
+
+        self.modify_action_trace(action_ordinal, |t| {
+            t.set_cpu_usage_us(cpu_used as u32);
+            t.set_net_usage_bytes(net_used as u32);
+        })?;
+
         // Finalize the apply context
         for (account, ram_delta) in apply_context.account_ram_deltas()?.iter() {
             self.add_ram_usage(account, *ram_delta)?;
@@ -308,6 +390,14 @@ impl TransactionContext {
         Ok(())
     }
 
+    /// The id of the transaction being applied, used to tag log lines so a
+    /// single transaction's progress through `exec`/`execute_action` can be
+    /// grepped out of the combined apply-pipeline log.
+    pub fn trx_id(&self) -> Result<Id, ChainError> {
+        let inner = self.inner.read()?;
+        Ok(inner.trace.id)
+    }
+
     pub fn get_action_trace(&self, action_ordinal: u32) -> Result<ActionTrace, ChainError> {
         let inner = self.inner.read()?;
         let trace = inner.trace.action_traces.get((action_ordinal as usize) - 1);
@@ -321,6 +411,21 @@ impl TransactionContext {
         }
     }
 
+    /// The account that dispatched `action_ordinal` via `send_inline`
+    /// (or `send_context_free_inline`), i.e. the receiver of its creator
+    /// action trace. The zero name for a top-level action, which nothing
+    /// scheduled.
+    pub fn get_sender(&self, action_ordinal: u32) -> Result<Name, ChainError> {
+        let creator_action_ordinal =
+            self.with_action_trace(action_ordinal, |t| t.creator_action_ordinal())?;
+
+        if creator_action_ordinal == 0 {
+            return Ok(Name::new(0));
+        }
+
+        self.with_action_trace(creator_action_ordinal, |t| t.receiver().clone())
+    }
+
     #[inline]
     fn with_action_trace_mut<R>(
         &self,
@@ -377,6 +482,23 @@ impl TransactionContext {
         inner.trace.receipt.status = TransactionStatus::Executed;
         inner.trace.receipt.net_usage_words = VarUint32((inner.trace.net_usage / 8) as u32);
 
+        // Aggregate every action's `account_ram_deltas` into a single
+        // `account_ram_delta` on the trace, the way explorers expect a quick
+        // "who paid for this transaction's RAM" answer without walking every
+        // action. `TransactionTrace::account_ram_delta` only holds one
+        // account, so if more than one account's usage moved in this
+        // transaction, the one with the first delta (by account name) wins.
+        let mut aggregate_ram_deltas: BTreeMap<Name, i64> = BTreeMap::new();
+        for action_trace in inner.trace.action_traces.iter() {
+            for (account, delta) in action_trace.account_ram_deltas.iter() {
+                *aggregate_ram_deltas.entry(account.clone()).or_insert(0) += delta;
+            }
+        }
+        inner.trace.account_ram_delta = aggregate_ram_deltas
+            .into_iter()
+            .find(|(_, delta)| *delta != 0)
+            .map(|(account, delta)| AccountDelta { account, delta });
+
         if inner.is_input {
             let trx = self.packed_transaction.get_transaction();
             let time: TimePoint = (&inner.pending_block_timestamp).into();
@@ -429,9 +551,8 @@ impl TransactionContext {
             .checked_add(cpu_usage)
             .ok_or_else(|| ChainError::ActionValidationError("CPU usage overflow".to_string()))?;
 
-        let total = u32::try_from(total).map_err(|_| {
-            ChainError::ActionValidationError("CPU usage overflow".to_string())
-        })?;
+        let total = u32::try_from(total)
+            .map_err(|_| ChainError::ActionValidationError("CPU usage overflow".to_string()))?;
 
         inner.trace.receipt.cpu_usage_us = total;
 
@@ -445,6 +566,33 @@ impl TransactionContext {
             .net_usage
             .checked_add(net_usage)
             .ok_or_else(|| ChainError::ActionValidationError("net usage overflow".to_string()))?;
+
+        // The leeway only applies while a block is being produced - once it
+        // has been produced, re-validating it checks against the real limit,
+        // so a producer can't use the leeway to sneak in an over-limit block.
+        let leeway = if self.block_status == BlockStatus::Building {
+            inner.net_usage_leeway
+        } else {
+            0
+        };
+
+        if inner.max_transaction_net_usage > 0
+            && inner.trace.net_usage > inner.max_transaction_net_usage + leeway
+        {
+            return Err(ChainError::TxNetUsageExceeded {
+                used: inner.trace.net_usage,
+                limit: inner.max_transaction_net_usage,
+            });
+        }
+
+        let block_net_limit = self.db.get_block_net_limit().unwrap_or(0);
+        if block_net_limit > 0 && inner.trace.net_usage > block_net_limit + leeway {
+            return Err(ChainError::BlockNetUsageExceeded {
+                used: inner.trace.net_usage,
+                limit: block_net_limit,
+            });
+        }
+
         Ok(())
     }
 
@@ -511,6 +659,53 @@ impl TransactionContext {
         &self.packed_transaction
     }
 
+    pub fn get_account(&self, name: Name) -> Result<&'static AccountObject, ChainError> {
+        {
+            let inner = self.inner.read()?;
+            if let Some(account) = inner.account_cache.get(&name) {
+                return Ok(*account);
+            }
+        }
+
+        let account = self.db.get_account(name.as_u64())?;
+        let mut inner = self.inner.write()?;
+        inner.account_cache.insert(name, account);
+        Ok(account)
+    }
+
+    pub fn get_account_metadata(
+        &self,
+        name: Name,
+    ) -> Result<&'static AccountMetadataObject, ChainError> {
+        {
+            let inner = self.inner.read()?;
+            if let Some(account) = inner.account_metadata_cache.get(&name) {
+                return Ok(*account);
+            }
+        }
+
+        let account = self.db.get_account_metadata(name.as_u64())?;
+        let mut inner = self.inner.write()?;
+        inner.account_metadata_cache.insert(name, account);
+        Ok(account)
+    }
+
+    /// Call once for every table iterator a `db_*_store`/`db_idx*_store`
+    /// host function opens, so the running total stays accurate across
+    /// every action in this transaction. Traps with [`ChainError`] once
+    /// [`DEFAULT_MAX_OPEN_ITERATORS_PER_TRANSACTION`] is exceeded.
+    pub fn note_iterator_opened(&self) -> Result<(), ChainError> {
+        let mut inner = self.inner.write()?;
+        inner.open_iterator_count += 1;
+        pulse_assert(
+            inner.open_iterator_count <= DEFAULT_MAX_OPEN_ITERATORS_PER_TRANSACTION,
+            ChainError::TransactionError(format!(
+                "transaction exceeded the maximum of {} open table iterators",
+                DEFAULT_MAX_OPEN_ITERATORS_PER_TRANSACTION
+            )),
+        )
+    }
+
     pub fn validate_expiration(&self, trx: &Transaction) -> Result<(), ChainError> {
         let inner = self.inner.read()?;
         let expiration: TimePoint = trx.header.expiration().into();
@@ -523,7 +718,10 @@ impl TransactionContext {
             ));
         }
 
-        if expiration > pending_block_timestamp + seconds(gpo.get_chain_config().get_max_transaction_lifetime() as i64) {
+        if expiration
+            > pending_block_timestamp
+                + seconds(gpo.get_chain_config().get_max_transaction_lifetime() as i64)
+        {
             return Err(ChainError::TransactionError(
                 "transaction has too long lifetime".to_string(),
             ));
@@ -595,3 +793,221 @@ impl TransactionContext {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, str::FromStr};
+
+    use pulsevm_ffi::TimePointSec;
+
+    use super::*;
+    use crate::{
+        crypto::PrivateKey,
+        transaction::{SignedTransaction, TransactionHeader},
+    };
+
+    fn open_temp_db() -> (tempfile::TempDir, Database) {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let mut db = Database::new(dir.path().to_str().unwrap(), 1024 * 1024 * 16)
+            .expect("failed to open database");
+        db.add_indices().unwrap();
+        (dir, db)
+    }
+
+    #[test]
+    fn test_trx_id_matches_the_transaction_it_was_built_for() {
+        let (_dir, db) = open_temp_db();
+        let wasm_runtime = WasmRuntime::new().unwrap();
+
+        let private_key =
+            PrivateKey::from_str("PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT")
+                .unwrap();
+        let chain_id =
+            Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
+                .unwrap();
+        let trx = SignedTransaction::new(
+            Transaction::new(
+                TransactionHeader::new(TimePointSec::new(100), 1, 2, 4.into(), 3, 5.into()),
+                vec![],
+                vec![],
+            ),
+            BTreeSet::new(),
+            vec![],
+        )
+        .sign(&private_key, &chain_id)
+        .unwrap();
+        let packed = PackedTransaction::from_signed_transaction(trx).unwrap();
+
+        // This is the id that `execute_action`'s debug logs tag every line
+        // with, so a single transaction's apply-pipeline progress can be
+        // correlated across log lines.
+        let context = TransactionContext::new(
+            db,
+            wasm_runtime,
+            1,
+            BlockTimestamp::default(),
+            packed.id(),
+            BlockStatus::Building,
+            packed.clone(),
+        );
+
+        assert_eq!(context.trx_id().unwrap(), *packed.id());
+    }
+
+    #[test]
+    fn test_account_metadata_cache_is_consistent_and_sees_later_modifications() {
+        let (_dir, mut db) = open_temp_db();
+        let name = Name::from_str("alice").unwrap();
+        db.create_account(name.as_u64(), 0).unwrap();
+        db.create_account_metadata(name.as_u64(), false).unwrap();
+
+        let wasm_runtime = WasmRuntime::new().unwrap();
+        let private_key =
+            PrivateKey::from_str("PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT")
+                .unwrap();
+        let chain_id =
+            Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
+                .unwrap();
+        let trx = SignedTransaction::new(
+            Transaction::new(
+                TransactionHeader::new(TimePointSec::new(100), 1, 2, 4.into(), 3, 5.into()),
+                vec![],
+                vec![],
+            ),
+            BTreeSet::new(),
+            vec![],
+        )
+        .sign(&private_key, &chain_id)
+        .unwrap();
+        let packed = PackedTransaction::from_signed_transaction(trx).unwrap();
+        let context = TransactionContext::new(
+            db.clone(),
+            wasm_runtime,
+            1,
+            BlockTimestamp::default(),
+            packed.id(),
+            BlockStatus::Building,
+            packed.clone(),
+        );
+
+        let first = context.get_account_metadata(name).unwrap();
+        assert!(!first.is_privileged());
+
+        // Same account, second lookup: should return the exact same cached
+        // reference rather than a fresh one.
+        let second = context.get_account_metadata(name).unwrap();
+        assert_eq!(
+            first as *const _, second as *const _,
+            "expected the cached reference to be reused"
+        );
+
+        // Chainbase modifies rows in place, so a mutation through the
+        // database is visible through the already-cached reference without
+        // needing to invalidate or re-fetch it.
+        db.set_privileged(name.as_u64(), true).unwrap();
+        assert!(second.is_privileged());
+    }
+
+    #[test]
+    fn test_note_iterator_opened_traps_once_the_cap_is_exceeded() {
+        let (_dir, db) = open_temp_db();
+        let wasm_runtime = WasmRuntime::new().unwrap();
+        let private_key =
+            PrivateKey::from_str("PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT")
+                .unwrap();
+        let chain_id =
+            Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
+                .unwrap();
+        let trx = SignedTransaction::new(
+            Transaction::new(
+                TransactionHeader::new(TimePointSec::new(100), 1, 2, 4.into(), 3, 5.into()),
+                vec![],
+                vec![],
+            ),
+            BTreeSet::new(),
+            vec![],
+        )
+        .sign(&private_key, &chain_id)
+        .unwrap();
+        let packed = PackedTransaction::from_signed_transaction(trx).unwrap();
+        let context = TransactionContext::new(
+            db,
+            wasm_runtime,
+            1,
+            BlockTimestamp::default(),
+            packed.id(),
+            BlockStatus::Building,
+            packed.clone(),
+        );
+
+        // Simulates a contract opening iterators across several actions of
+        // the same transaction: every call shares the one running total.
+        for _ in 0..DEFAULT_MAX_OPEN_ITERATORS_PER_TRANSACTION {
+            context.note_iterator_opened().unwrap();
+        }
+
+        let err = context.note_iterator_opened().unwrap_err();
+        assert!(matches!(err, ChainError::TransactionError(_)));
+    }
+
+    #[test]
+    fn test_add_net_usage_keeps_the_unrounded_total_and_rejects_once_leeway_is_exhausted() {
+        let (_dir, db) = open_temp_db();
+        let wasm_runtime = WasmRuntime::new().unwrap();
+        let private_key =
+            PrivateKey::from_str("PVT_K1_2pjSqJxTbRHq8h8aHHTux81Ypscb36Q2syB8UJbZcUmxbfZdnT")
+                .unwrap();
+        let chain_id =
+            Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
+                .unwrap();
+        let trx = SignedTransaction::new(
+            Transaction::new(
+                TransactionHeader::new(TimePointSec::new(100), 1, 2, 4.into(), 3, 5.into()),
+                vec![],
+                vec![],
+            ),
+            BTreeSet::new(),
+            vec![],
+        )
+        .sign(&private_key, &chain_id)
+        .unwrap();
+        let packed = PackedTransaction::from_signed_transaction(trx).unwrap();
+        let context = TransactionContext::new(
+            db,
+            wasm_runtime,
+            1,
+            BlockTimestamp::default(),
+            packed.id(),
+            BlockStatus::Building,
+            packed.clone(),
+        );
+
+        {
+            let mut inner = context.inner.write().unwrap();
+            inner.max_transaction_net_usage = 100;
+            inner.net_usage_leeway = 10;
+        }
+
+        // Within the raw limit: no error, and the trace keeps the
+        // unrounded running total (rounding to words only happens once, in
+        // `finalize()`).
+        context.add_net_usage(100).unwrap();
+        assert_eq!(context.inner.read().unwrap().trace.net_usage, 100);
+
+        // Over the raw limit, but still inside the leeway this block is
+        // being built with.
+        context.add_net_usage(10).unwrap();
+        assert_eq!(context.inner.read().unwrap().trace.net_usage, 110);
+
+        // Past even the leeway: rejected as a transaction net-usage error,
+        // not a block one, since the transaction limit is the tighter one.
+        let err = context.add_net_usage(1).unwrap_err();
+        assert!(matches!(
+            err,
+            ChainError::TxNetUsageExceeded {
+                used: 111,
+                limit: 100
+            }
+        ));
+    }
+}
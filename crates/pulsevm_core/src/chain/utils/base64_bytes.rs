@@ -1,4 +1,4 @@
-use base64::{Engine, prelude::BASE64_STANDARD};
+use base64::{prelude::BASE64_STANDARD, Engine};
 use pulsevm_proc_macros::{NumBytes, Read, Write};
 use serde::Serialize;
 
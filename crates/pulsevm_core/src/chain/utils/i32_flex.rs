@@ -3,8 +3,8 @@
 
 use core::fmt;
 use serde::{
-    Deserialize, Deserializer, Serialize, Serializer,
     de::{Error as DeError, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
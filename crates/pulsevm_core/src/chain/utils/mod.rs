@@ -7,6 +7,9 @@ pub use digest::*;
 mod i32_flex;
 pub use i32_flex::*;
 
+mod secondary_key;
+pub use secondary_key::*;
+
 mod usage_accumulator;
 pub use usage_accumulator::*;
 
@@ -0,0 +1,57 @@
+/// Packs two `u64` fields into a single `u128` secondary key for
+/// `db_idx128` (e.g. a `(owner, symbol)` composite index), the same trick
+/// EOSIO's own `combine_ids`-style helpers use for `checksum128`/
+/// `checksum256` secondary keys: the major field occupies the high 64 bits
+/// and the minor field the low 64 bits. Comparing two packed keys as plain
+/// integers then orders by the major field first and the minor field
+/// second, so a contract can range-scan every row sharing a major field
+/// with `db_idx128_lowerbound`/`db_idx128_upperbound` by fixing the major
+/// half and letting the minor half range over `0..=u64::MAX`.
+#[inline]
+pub fn combine_secondary_key128(major: u64, minor: u64) -> u128 {
+    ((major as u128) << 64) | minor as u128
+}
+
+/// The first packed key that can belong to `major`'s range, i.e. `(major, 0)`.
+/// Feed this to `db_idx128_lowerbound` to find the first row for `major`.
+#[inline]
+pub fn secondary_key128_range_start(major: u64) -> u128 {
+    combine_secondary_key128(major, 0)
+}
+
+/// The last packed key that can belong to `major`'s range, i.e.
+/// `(major, u64::MAX)`. Feed this to `db_idx128_upperbound` to find the
+/// first row *past* `major`'s range.
+#[inline]
+pub fn secondary_key128_range_end(major: u64) -> u128 {
+    combine_secondary_key128(major, u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_major_into_the_high_bits() {
+        assert_eq!(combine_secondary_key128(1, 0), 1u128 << 64);
+        assert_eq!(combine_secondary_key128(0, 1), 1u128);
+        assert_eq!(combine_secondary_key128(100, 2), (100u128 << 64) | 2);
+    }
+
+    #[test]
+    fn ordering_matches_major_then_minor() {
+        // (100, 2) < (200, 1) even though 2 > 1: the major field dominates.
+        assert!(combine_secondary_key128(100, 2) < combine_secondary_key128(200, 1));
+        // Within the same major, the minor field breaks ties.
+        assert!(combine_secondary_key128(100, 1) < combine_secondary_key128(100, 2));
+    }
+
+    #[test]
+    fn range_bounds_cover_every_minor_value_for_a_major() {
+        let start = secondary_key128_range_start(100);
+        let end = secondary_key128_range_end(100);
+        assert!(start <= combine_secondary_key128(100, 0));
+        assert!(end >= combine_secondary_key128(100, u64::MAX));
+        assert!(end < combine_secondary_key128(101, 0));
+    }
+}
@@ -8,17 +8,18 @@ use pulsevm_crypto::Bytes;
 use pulsevm_error::ChainError;
 use pulsevm_ffi::{BlockTimestamp, CxxDigest, Database};
 use wasmer::{
-    Engine, Function, FunctionEnv, Instance, Memory, Module, Store, imports, sys::CompilerConfig,
-    wasmparser::Operator,
+    imports, sys::CompilerConfig, wasmparser::Operator, Engine, Function, FunctionEnv, Instance,
+    Memory, Module, Store,
 };
-use wasmer_compiler_llvm::{LLVM, LLVMOptLevel};
+use wasmer_compiler_llvm::{LLVMOptLevel, LLVM};
 use wasmer_middlewares::{
+    metering::{get_remaining_points, set_remaining_points, MeteringPoints},
     Metering,
-    metering::{MeteringPoints, get_remaining_points, set_remaining_points},
 };
 
 use crate::chain::{
     apply_context::ApplyContext,
+    call_depth_limit::{CallDepthLimit, DEFAULT_MAX_CALL_DEPTH},
     id::Id,
     name::Name,
     transaction::Action,
@@ -31,35 +32,36 @@ use crate::chain::{
         __trunctfdf2, __trunctfsf2, __udivti3, __umodti3, __unordtf2, abort, assert_recover_key,
         assert_ripemd160, assert_sha1, assert_sha224, assert_sha256, assert_sha512,
         check_permission_authorization, check_transaction_authorization, current_time, db_end_i64,
-        db_find_i64, db_get_i64, db_idx_double_end, db_idx_double_find_primary,
+        db_find_i64, db_get_i64, db_idx128_end, db_idx128_find_primary, db_idx128_find_secondary,
+        db_idx128_lowerbound, db_idx128_next, db_idx128_previous, db_idx128_remove,
+        db_idx128_store, db_idx128_update, db_idx128_upperbound, db_idx256_end,
+        db_idx256_find_primary, db_idx256_find_secondary, db_idx256_lowerbound, db_idx256_next,
+        db_idx256_previous, db_idx256_remove, db_idx256_store, db_idx256_update,
+        db_idx256_upperbound, db_idx64_end, db_idx64_find_primary, db_idx64_find_secondary,
+        db_idx64_lowerbound, db_idx64_next, db_idx64_previous, db_idx64_remove, db_idx64_store,
+        db_idx64_update, db_idx64_upperbound, db_idx_double_end, db_idx_double_find_primary,
         db_idx_double_find_secondary, db_idx_double_lowerbound, db_idx_double_next,
         db_idx_double_previous, db_idx_double_remove, db_idx_double_store, db_idx_double_update,
         db_idx_double_upperbound, db_idx_long_double_end, db_idx_long_double_find_primary,
         db_idx_long_double_find_secondary, db_idx_long_double_lowerbound, db_idx_long_double_next,
         db_idx_long_double_previous, db_idx_long_double_remove, db_idx_long_double_store,
-        db_idx_long_double_update, db_idx_long_double_upperbound, db_idx64_end,
-        db_idx64_find_primary, db_idx64_find_secondary, db_idx64_lowerbound, db_idx64_next,
-        db_idx64_previous, db_idx64_remove, db_idx64_store, db_idx64_update, db_idx64_upperbound,
-        db_idx128_end, db_idx128_find_primary, db_idx128_find_secondary, db_idx128_lowerbound,
-        db_idx128_next, db_idx128_previous, db_idx128_remove, db_idx128_store, db_idx128_update,
-        db_idx128_upperbound, db_idx256_end, db_idx256_find_primary, db_idx256_find_secondary,
-        db_idx256_lowerbound, db_idx256_next, db_idx256_previous, db_idx256_remove,
-        db_idx256_store, db_idx256_update, db_idx256_upperbound, db_lowerbound_i64, db_next_i64,
+        db_idx_long_double_update, db_idx_long_double_upperbound, db_lowerbound_i64, db_next_i64,
         db_previous_i64, db_remove_i64, db_store_i64, db_update_i64, db_upperbound_i64,
         eosio_assert, expiration, get_account_creation_time, get_action, get_active_producers,
-        get_blockchain_parameters_packed, get_context_free_data, get_permission_last_used,
-        get_resource_limits, is_privileged, memcmp, memcpy, memmove, memset, printdf, printhex,
-        printi, printi128, printn, printqf, prints, prints_l, printsf, printui, printui128,
-        pulse_assert, pulse_assert_code, pulse_assert_message, pulse_exit, read_action_data,
-        read_transaction, recover_key, require_auth2, require_recipient, ripemd160,
-        send_context_free_inline, set_action_return_value, set_blockchain_parameters_packed,
-        set_privileged, set_proposed_producers, set_resource_limits, sha1, sha224, sha256, sha512,
-        tapos_block_num, tapos_block_prefix, transaction_size,
+        get_block_random_seed, get_blockchain_parameters_packed, get_context_free_data,
+        get_permission_last_used, get_resource_limits, is_privileged, memcmp, memcpy, memmove,
+        memset, printdf, printhex, printi, printi128, printn, printqf, prints, prints_l, printsf,
+        printui, printui128, pulse_assert, pulse_assert_code, pulse_assert_message, pulse_exit,
+        read_action_data, read_transaction, recover_key, require_auth2, require_recipient,
+        ripemd160, send_context_free_inline, set_action_return_value,
+        set_blockchain_parameters_packed, set_privileged, set_proposed_producers,
+        set_resource_limits, sha1, sha224, sha256, sha512, tapos_block_num, tapos_block_prefix,
+        transaction_size,
     },
 };
 
 use super::webassembly::{
-    action_data_size, current_receiver, has_auth, is_account, require_auth, send_inline,
+    action_data_size, current_receiver, get_sender, has_auth, is_account, require_auth, send_inline,
 };
 
 pub struct WasmContext {
@@ -176,6 +178,208 @@ const COST_FUNCTION: fn(&Operator) -> u64 = |operator: &Operator| -> u64 {
     }
 };
 
+// Every host function this runtime actually registers under the "env"
+// module namespace. Kept in sync with the `imports!` block in `run` below.
+// Anything a contract imports outside this set gets rejected before
+// instantiation: a consensus-critical WASM must only touch the
+// deterministic intrinsics we provide, never a source of nondeterminism
+// like a real clock or randomness.
+const ALLOWED_ENV_IMPORTS: &[&str] = &[
+    "memcpy",
+    "memset",
+    "memcmp",
+    "memmove",
+    "__ashlti3",
+    "__ashrti3",
+    "__lshlti3",
+    "__lshrti3",
+    "__divti3",
+    "__udivti3",
+    "__multi3",
+    "__modti3",
+    "__umodti3",
+    "__addtf3",
+    "__subtf3",
+    "__multf3",
+    "__divtf3",
+    "__negtf2",
+    "__extendsftf2",
+    "__extenddftf2",
+    "__trunctfdf2",
+    "__trunctfsf2",
+    "__fixtfsi",
+    "__fixtfdi",
+    "__fixtfti",
+    "__fixunstfsi",
+    "__fixunstfti",
+    "__fixsfti",
+    "__fixdfti",
+    "__fixunssfti",
+    "__fixunsdfti",
+    "__floatsidf",
+    "__floatsitf",
+    "__floatditf",
+    "__floatunsitf",
+    "__floatunditf",
+    "__floattidf",
+    "__floatuntidf",
+    "__eqtf2",
+    "__netf2",
+    "__getf2",
+    "__gttf2",
+    "__letf2",
+    "__lttf2",
+    "__cmptf2",
+    "__unordtf2",
+    "action_data_size",
+    "read_action_data",
+    "current_receiver",
+    "get_sender",
+    "set_action_return_value",
+    "require_auth",
+    "has_auth",
+    "require_auth2",
+    "require_recipient",
+    "is_account",
+    "db_find_i64",
+    "db_store_i64",
+    "db_get_i64",
+    "db_update_i64",
+    "db_remove_i64",
+    "db_next_i64",
+    "db_previous_i64",
+    "db_end_i64",
+    "db_lowerbound_i64",
+    "db_upperbound_i64",
+    "db_idx64_store",
+    "db_idx64_update",
+    "db_idx64_remove",
+    "db_idx64_find_secondary",
+    "db_idx64_find_primary",
+    "db_idx64_lowerbound",
+    "db_idx64_upperbound",
+    "db_idx64_end",
+    "db_idx64_next",
+    "db_idx64_previous",
+    "db_idx128_store",
+    "db_idx128_update",
+    "db_idx128_remove",
+    "db_idx128_find_secondary",
+    "db_idx128_find_primary",
+    "db_idx128_lowerbound",
+    "db_idx128_upperbound",
+    "db_idx128_end",
+    "db_idx128_next",
+    "db_idx128_previous",
+    "db_idx256_store",
+    "db_idx256_update",
+    "db_idx256_remove",
+    "db_idx256_find_secondary",
+    "db_idx256_find_primary",
+    "db_idx256_lowerbound",
+    "db_idx256_upperbound",
+    "db_idx256_end",
+    "db_idx256_next",
+    "db_idx256_previous",
+    "db_idx_double_store",
+    "db_idx_double_update",
+    "db_idx_double_remove",
+    "db_idx_double_find_secondary",
+    "db_idx_double_find_primary",
+    "db_idx_double_lowerbound",
+    "db_idx_double_upperbound",
+    "db_idx_double_end",
+    "db_idx_double_next",
+    "db_idx_double_previous",
+    "db_idx_long_double_store",
+    "db_idx_long_double_update",
+    "db_idx_long_double_remove",
+    "db_idx_long_double_find_secondary",
+    "db_idx_long_double_find_primary",
+    "db_idx_long_double_lowerbound",
+    "db_idx_long_double_upperbound",
+    "db_idx_long_double_end",
+    "db_idx_long_double_next",
+    "db_idx_long_double_previous",
+    "pulse_assert",
+    "eosio_assert",
+    "pulse_assert_message",
+    "eosio_assert_message",
+    "pulse_assert_code",
+    "eosio_assert_code",
+    "pulse_exit",
+    "eosio_exit",
+    "abort",
+    "current_time",
+    "get_block_random_seed",
+    "assert_recover_key",
+    "recover_key",
+    "sha1",
+    "sha224",
+    "sha256",
+    "sha512",
+    "ripemd160",
+    "assert_sha1",
+    "assert_sha224",
+    "assert_sha256",
+    "assert_sha512",
+    "assert_ripemd160",
+    "is_privileged",
+    "set_privileged",
+    "set_proposed_producers",
+    "get_blockchain_parameters_packed",
+    "set_blockchain_parameters_packed",
+    "set_resource_limits",
+    "get_resource_limits",
+    "send_inline",
+    "send_context_free_inline",
+    "read_transaction",
+    "transaction_size",
+    "expiration",
+    "tapos_block_num",
+    "tapos_block_prefix",
+    "get_action",
+    "prints",
+    "prints_l",
+    "printi",
+    "printui",
+    "printi128",
+    "printui128",
+    "printsf",
+    "printdf",
+    "printqf",
+    "printn",
+    "printhex",
+    "check_transaction_authorization",
+    "check_permission_authorization",
+    "get_permission_last_used",
+    "get_account_creation_time",
+    "get_context_free_data",
+    "get_active_producers",
+];
+
+/// Rejects any import the module pulls in that isn't one of our registered
+/// deterministic intrinsics. `Instance::new` would eventually fail on an
+/// unresolved import too, but that failure mode is a generic link error;
+/// this gives contract authors and node operators an explicit, early
+/// `ChainError` naming exactly which import tripped the guard. Since no
+/// randomness source is ever added to `ALLOWED_ENV_IMPORTS`, this also
+/// guarantees no contract can reach real non-determinism through a host
+/// function - `get_block_random_seed` is the deterministic substitute.
+fn validate_imports(module: &Module) -> Result<(), ChainError> {
+    for import in module.imports() {
+        if import.module() != "env" || !ALLOWED_ENV_IMPORTS.contains(&import.name()) {
+            return Err(ChainError::WasmRuntimeError(format!(
+                "disallowed import: {}.{}",
+                import.module(),
+                import.name()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 impl WasmRuntime {
     pub fn new() -> Result<Self, ChainError> {
         let mut compiler = LLVM::default();
@@ -217,6 +421,8 @@ impl WasmRuntime {
                 // Add initial limit of 1,000 so start function can run if present
                 let metering = Arc::new(Metering::new(1_000, COST_FUNCTION));
                 compiler.push_middleware(metering);
+                let call_depth_limit = Arc::new(CallDepthLimit::new(DEFAULT_MAX_CALL_DEPTH));
+                compiler.push_middleware(call_depth_limit);
                 LLVM::canonicalize_nans(&mut compiler, true);
                 LLVM::opt_level(&mut compiler, LLVMOptLevel::Aggressive);
 
@@ -299,6 +505,7 @@ impl WasmRuntime {
                 "action_data_size" => Function::new_typed_with_env(&mut store, &env, action_data_size),
                 "read_action_data" => Function::new_typed_with_env(&mut store, &env, read_action_data),
                 "current_receiver" => Function::new_typed_with_env(&mut store, &env, current_receiver),
+                "get_sender" => Function::new_typed_with_env(&mut store, &env, get_sender),
                 "set_action_return_value" => Function::new_typed_with_env(&mut store, &env, set_action_return_value),
                 "require_auth" => Function::new_typed_with_env(&mut store, &env, require_auth),
                 "has_auth" => Function::new_typed_with_env(&mut store, &env, has_auth),
@@ -382,6 +589,7 @@ impl WasmRuntime {
                 "eosio_exit" => Function::new_typed_with_env(&mut store, &env, pulse_exit),
                 "abort" => Function::new_typed_with_env(&mut store, &env, abort),
                 "current_time" => Function::new_typed_with_env(&mut store, &env, current_time),
+                "get_block_random_seed" => Function::new_typed_with_env(&mut store, &env, get_block_random_seed),
                 // Crypto functions
                 "assert_recover_key" => Function::new_typed_with_env(&mut store, &env, assert_recover_key),
                 "recover_key" => Function::new_typed_with_env(&mut store, &env, recover_key),
@@ -435,6 +643,8 @@ impl WasmRuntime {
                 "get_active_producers" => Function::new_typed_with_env(&mut store, &env, get_active_producers),
             }
         };
+        validate_imports(&module.module)?;
+
         let instance = Instance::new(&mut store, &module.module, &import_object).map_err(|e| {
             ChainError::WasmRuntimeError(format!("failed to create wasm instance: {}", e))
         })?;
@@ -482,6 +692,20 @@ impl WasmRuntime {
                     return chain_err.clone();
                 }
 
+                // A trap code means the runtime itself aborted execution —
+                // `unreachable`, an out-of-bounds access, integer division
+                // by zero — rather than the contract explicitly asserting
+                // `false`, which surfaces as a plain user error with no
+                // trap code attached.
+                if let Some(trap_code) = e.clone().to_trap() {
+                    let offset = e
+                        .trace()
+                        .first()
+                        .map(|frame| format!(" at offset {}", frame.module_offset()))
+                        .unwrap_or_default();
+                    return ChainError::WasmTrap(format!("{}{}", trap_code.message(), offset));
+                }
+
                 // Otherwise wrap it
                 ChainError::ApplyError(format!("{}", e.message()))
             });
@@ -41,6 +41,17 @@ pub fn current_receiver(env: FunctionEnvMut<WasmContext>) -> u64 {
     env.data().receiver()
 }
 
+#[inline]
+pub fn get_sender(env: FunctionEnvMut<WasmContext>) -> Result<u64, RuntimeError> {
+    context_aware_check(&env)?;
+    let sender = env
+        .data()
+        .apply_context()
+        .get_sender()
+        .map_err(|e| RuntimeError::new(e.to_string()))?;
+    Ok(sender.as_u64())
+}
+
 #[inline]
 pub fn set_action_return_value(
     mut env: FunctionEnvMut<WasmContext>,
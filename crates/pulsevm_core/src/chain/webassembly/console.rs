@@ -1,21 +1,45 @@
 use wasmer::{FunctionEnvMut, RuntimeError, WasmPtr};
 
-use crate::wasm_runtime::WasmContext;
+use crate::{chain::webassembly::context_aware_check, wasm_runtime::WasmContext};
 
-// TODO: Implement console functions to log output from WASM modules. For now, these functions are no-ops to avoid unnecessary overhead in the current implementation.
+// Numeric/float/hex print functions below remain no-ops: the action trace's
+// `console` buffer only captures the string prints (`prints`/`prints_l`),
+// matching what the request asked the cap to bound.
 
 pub fn prints(
-    _env: FunctionEnvMut<WasmContext>,
-    _msg_ptr: WasmPtr<u8>,
+    mut env: FunctionEnvMut<WasmContext>,
+    msg_ptr: WasmPtr<u8>,
 ) -> Result<(), RuntimeError> {
+    context_aware_check(&env)?;
+    let (env_data, store) = env.data_and_store_mut();
+    let memory = env_data
+        .memory()
+        .as_ref()
+        .expect("Wasm memory not initialized");
+    let view = memory.view(&store);
+    let msg = msg_ptr.read_utf8_string_with_nul(&view)?;
+
+    let context = env_data.apply_context_mut();
+    context.console_append(&msg)?;
     Ok(())
 }
 
 pub fn prints_l(
-    _env: FunctionEnvMut<WasmContext>,
-    _msg_ptr: WasmPtr<u8>,
-    _msg_len: u32,
+    mut env: FunctionEnvMut<WasmContext>,
+    msg_ptr: WasmPtr<u8>,
+    msg_len: u32,
 ) -> Result<(), RuntimeError> {
+    context_aware_check(&env)?;
+    let (env_data, store) = env.data_and_store_mut();
+    let memory = env_data
+        .memory()
+        .as_ref()
+        .expect("Wasm memory not initialized");
+    let view = memory.view(&store);
+    let msg = msg_ptr.read_utf8_string(&view, msg_len)?;
+
+    let context = env_data.apply_context_mut();
+    context.console_append(&msg)?;
     Ok(())
 }
 
@@ -1,6 +1,7 @@
+use sha2::Digest;
 use wasmer::{FunctionEnvMut, RuntimeError, WasmPtr};
 
-use crate::chain::{wasm_runtime::WasmContext, webassembly::context_aware_check};
+use crate::chain::{id::Id, wasm_runtime::WasmContext, webassembly::context_aware_check};
 
 const MAX_ASSERT_MESSAGE: usize = 1024;
 
@@ -155,3 +156,76 @@ pub fn current_time(env: FunctionEnvMut<WasmContext>) -> Result<u64, RuntimeErro
 
     Ok(result as u64)
 }
+
+/// Derives a 32-byte seed from `slot`/`action_ordinal`/`trx_id` alone, with
+/// no other input - so the same block slot, action ordinal and transaction
+/// id always hash to the same seed, whether this runs during validation,
+/// replay, or any other re-execution of that action.
+fn compute_block_random_seed(slot: u32, action_ordinal: u32, trx_id: &Id) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(4 + 4 + 32);
+    preimage.extend_from_slice(&slot.to_le_bytes());
+    preimage.extend_from_slice(&action_ordinal.to_le_bytes());
+    preimage.extend_from_slice(trx_id.as_bytes());
+
+    sha2::Sha256::digest(&preimage).into()
+}
+
+/// Writes a 32-byte digest derived from the pending block's slot, the
+/// current action's ordinal and the current transaction's id into `out_ptr`. This is NOT a randomness source
+/// - it's the same value every time the same action is applied in the same
+/// block, which is the point: contracts that want reproducible
+/// "randomness" (e.g. to pick among several valid outcomes) can hash this
+/// seed with their own action data instead of reaching for a non-deterministic
+/// source, which the WASM import allowlist in `wasm_runtime.rs` rejects
+/// outright.
+pub fn get_block_random_seed(
+    mut env: FunctionEnvMut<WasmContext>,
+    out_ptr: WasmPtr<u8>,
+) -> Result<(), RuntimeError> {
+    context_aware_check(&env)?;
+
+    let apply_context = env.data().apply_context();
+    let slot = apply_context.pending_block_timestamp().slot();
+    let action_ordinal = apply_context.action_ordinal();
+    let trx_id = apply_context
+        .trx_id()
+        .map_err(|e| RuntimeError::new(e.to_string()))?;
+
+    let seed = compute_block_random_seed(slot, action_ordinal, &trx_id);
+
+    let (env_data, store) = env.data_and_store_mut();
+    let memory = env_data
+        .memory()
+        .as_ref()
+        .expect("Wasm memory not initialized");
+    let view = memory.view(&store);
+    let slice = out_ptr.slice(&view, seed.len() as u32)?;
+    slice.write_slice(seed.as_ref())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_random_seed_is_identical_across_two_runs_of_the_same_block_and_action() {
+        let trx_id = Id::new([7u8; 32]);
+
+        let seed_a = compute_block_random_seed(42, 1, &trx_id);
+        let seed_b = compute_block_random_seed(42, 1, &trx_id);
+
+        assert_eq!(seed_a, seed_b);
+    }
+
+    #[test]
+    fn test_block_random_seed_changes_with_action_ordinal() {
+        let trx_id = Id::new([7u8; 32]);
+
+        let seed_a = compute_block_random_seed(42, 1, &trx_id);
+        let seed_b = compute_block_random_seed(42, 2, &trx_id);
+
+        assert_ne!(seed_a, seed_b);
+    }
+}
@@ -0,0 +1,48 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use pulsevm_crypto::{Digest, HashWriter};
+use pulsevm_proc_macros::{NumBytes, Write as WriteDerive};
+use pulsevm_serialization::Write;
+
+#[derive(Clone, Copy, NumBytes, WriteDerive)]
+struct Sample {
+    account: u64,
+    name: u64,
+    global_sequence: u64,
+    recv_sequence: u64,
+}
+
+fn pack_then_hash(value: &Sample) {
+    for _ in 0..1_000_000 {
+        let packed = black_box(value).pack().unwrap();
+        let _ = black_box(Digest::hash(&packed));
+    }
+}
+
+fn reused_hash_writer(value: &Sample) {
+    let mut writer = HashWriter::new();
+    for _ in 0..1_000_000 {
+        let _ = black_box(writer.hash(black_box(value)).unwrap());
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let value = Sample {
+        account: 1,
+        name: 2,
+        global_sequence: 3,
+        recv_sequence: 4,
+    };
+
+    c.bench_function("pack then hash 1_000_000 times", |b| {
+        b.iter(|| pack_then_hash(black_box(&value)))
+    });
+
+    c.bench_function("reused HashWriter 1_000_000 times", |b| {
+        b.iter(|| reused_hash_writer(black_box(&value)))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 use core::fmt;
 
-use pulsevm_serialization::{NumBytes, Read, ReadError, Write};
+use pulsevm_serialization::{NumBytes, Read, ReadError, ReadRef, Write};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
@@ -127,6 +127,49 @@ impl AsRef<[u8]> for Bytes {
     }
 }
 
+/// The zero-copy counterpart to [`Bytes`]: wraps a slice borrowed straight
+/// out of the wire buffer instead of an owned `Vec<u8>`, for callers on a hot
+/// path (e.g. hashing or validating contract code in `setcode`) that don't
+/// need an owned copy until the data is actually stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BytesRef<'a>(pub &'a [u8]);
+
+impl<'a> BytesRef<'a> {
+    #[inline]
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.0
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub fn to_owned(&self) -> Bytes {
+        Bytes(self.0.to_vec())
+    }
+}
+
+impl<'a> ReadRef<'a> for BytesRef<'a> {
+    #[inline]
+    fn read_ref(bytes: &'a [u8], pos: &mut usize) -> Result<Self, ReadError> {
+        <&'a [u8]>::read_ref(bytes, pos).map(BytesRef)
+    }
+}
+
+impl<'a> AsRef<[u8]> for BytesRef<'a> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +179,25 @@ mod tests {
         let bytes = Bytes::new(vec![0x12, 0x34, 0x56, 0x78]);
         assert_eq!(bytes.to_string(), "12345678");
     }
+
+    #[test]
+    fn bytes_ref_aliases_the_input_buffer_and_matches_the_owned_read() {
+        let input = Bytes::new(vec![0xde, 0xad, 0xbe, 0xef]).pack().unwrap();
+
+        let mut ref_pos = 0;
+        let borrowed = BytesRef::read_ref(&input, &mut ref_pos).unwrap();
+
+        let mut owned_pos = 0;
+        let owned = Bytes::read(&input, &mut owned_pos).unwrap();
+
+        assert_eq!(ref_pos, owned_pos);
+        assert_eq!(borrowed.as_slice(), owned.as_slice());
+        assert_eq!(borrowed.to_owned(), owned);
+
+        // The whole point: no copy happened, so the borrowed slice's data
+        // pointer lands inside `input`'s own allocation.
+        let input_range = input.as_ptr_range();
+        let borrowed_ptr = borrowed.as_slice().as_ptr();
+        assert!(input_range.contains(&borrowed_ptr));
+    }
 }
@@ -0,0 +1,97 @@
+use std::cell::RefCell;
+
+use pulsevm_serialization::{Write, WriteError};
+
+use crate::Digest;
+
+/// A reusable scratch buffer for hashing packed values.
+///
+/// `pulsevm_serialization::Write` only knows how to write into an
+/// already-sized `&mut [u8]`, so a single call to [`HashWriter::hash`] still
+/// has to size and fill a buffer before it can be hashed - there's no way to
+/// stream bytes straight into the hasher one field at a time. What a
+/// `HashWriter` buys instead is reuse: the same `Vec<u8>` is cleared and
+/// refilled on every call rather than allocated fresh, so hashing many
+/// values in a row (e.g. every block's transactions) only pays for the
+/// allocation once the buffer has grown to the largest value seen so far.
+#[derive(Default)]
+pub struct HashWriter {
+    buf: Vec<u8>,
+}
+
+impl HashWriter {
+    #[inline]
+    pub fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    /// Packs `value` into this writer's scratch buffer and hashes it -
+    /// equivalent to `Digest::hash(&value.pack()?)`, but without allocating
+    /// a fresh `Vec` when this writer has already hashed something this
+    /// size or larger.
+    pub fn hash<T: Write>(&mut self, value: &T) -> Result<Digest, WriteError> {
+        let num_bytes = value.num_bytes();
+        self.buf.clear();
+        self.buf.resize(num_bytes, 0);
+        value.write(&mut self.buf, &mut 0)?;
+        Ok(Digest::hash(self.buf.as_slice()))
+    }
+}
+
+thread_local! {
+    static SCRATCH: RefCell<HashWriter> = RefCell::new(HashWriter::new());
+}
+
+/// Hashes `value` the way `Digest::hash(&value.pack()?)` does, reusing a
+/// thread-local [`HashWriter`] so repeated calls on the same thread don't
+/// keep allocating a new buffer. Prefer a hot loop's own [`HashWriter`] over
+/// this if the caller already owns one - it avoids the thread-local lookup.
+pub fn hash_packed<T: Write>(value: &T) -> Result<Digest, WriteError> {
+    SCRATCH.with(|scratch| scratch.borrow_mut().hash(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use pulsevm_proc_macros::{NumBytes, Write as WriteDerive};
+
+    use super::*;
+
+    #[derive(Clone, Copy, NumBytes, WriteDerive)]
+    struct Sample {
+        a: u64,
+        b: u32,
+        c: u16,
+    }
+
+    #[test]
+    fn hash_packed_matches_hashing_the_packed_bytes() {
+        let value = Sample {
+            a: 0xdead_beef_cafe_f00d,
+            b: 0x1234_5678,
+            c: 0xabcd,
+        };
+
+        let expected = Digest::hash(value.pack().unwrap());
+        assert_eq!(hash_packed(&value).unwrap(), expected);
+    }
+
+    #[test]
+    fn a_reused_hash_writer_produces_the_same_digest_every_time() {
+        let mut writer = HashWriter::new();
+
+        let small = Sample { a: 1, b: 2, c: 3 };
+        let large = Sample {
+            a: u64::MAX,
+            b: u32::MAX,
+            c: u16::MAX,
+        };
+
+        let first = writer.hash(&large).unwrap();
+        // Reusing the writer for a smaller value afterwards must not leave
+        // any of the previous, larger write behind in the hashed bytes.
+        let second = writer.hash(&small).unwrap();
+
+        assert_eq!(first, Digest::hash(large.pack().unwrap()));
+        assert_eq!(second, Digest::hash(small.pack().unwrap()));
+    }
+}
@@ -0,0 +1,128 @@
+use ripemd::{Digest as RipemdDigest, Ripemd160};
+use sha2::Sha256;
+
+/// The suffix mixed into the RIPEMD-160 checksum of the `PUB_K1_`/`PVT_K1_`
+/// formats, so a checksummed key can't be reinterpreted as a different key
+/// type. The legacy formats below predate this and checksum the raw bytes
+/// alone.
+const K1_CHECKSUM_SUFFIX: &[u8] = b"K1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum LegacyKeyError {
+    #[error("not base58")]
+    NotBase58,
+    #[error("wrong length for a legacy key")]
+    WrongLength,
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+}
+
+fn ripemd160(data: &[u8]) -> [u8; 20] {
+    let mut hasher = Ripemd160::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// Converts a legacy `EOS...` public key (no delimiter, checksummed with a
+/// plain `ripemd160(raw)` over the 33 compressed secp256k1 bytes) into the
+/// equivalent `PUB_K1_...` string, validating the checksum along the way.
+pub fn legacy_public_key_to_pub_k1(legacy_base58: &str) -> Result<String, LegacyKeyError> {
+    let legacy_base58 = legacy_base58
+        .strip_prefix("EOS")
+        .ok_or(LegacyKeyError::WrongLength)?;
+    let decoded = bs58::decode(legacy_base58)
+        .into_vec()
+        .map_err(|_| LegacyKeyError::NotBase58)?;
+    if decoded.len() != 33 + 4 {
+        return Err(LegacyKeyError::WrongLength);
+    }
+    let (raw, checksum) = decoded.split_at(33);
+    if ripemd160(raw)[..4] != checksum[..4] {
+        return Err(LegacyKeyError::ChecksumMismatch);
+    }
+
+    Ok(format!("PUB_K1_{}", encode_with_k1_checksum(raw)))
+}
+
+/// Converts a legacy WIF private key (Bitcoin mainnet version byte `0x80`,
+/// double-SHA256 checksum over a raw 32-byte secret) into the equivalent
+/// `PVT_K1_...` string, validating the checksum along the way. Mirrors the
+/// vendored `fc::crypto::private_key::from_wif`'s leniency of accepting
+/// either a single or double SHA256 checksum.
+pub fn legacy_wif_to_pvt_k1(wif_base58: &str) -> Result<String, LegacyKeyError> {
+    let decoded = bs58::decode(wif_base58)
+        .into_vec()
+        .map_err(|_| LegacyKeyError::NotBase58)?;
+    if decoded.len() != 1 + 32 + 4 {
+        return Err(LegacyKeyError::WrongLength);
+    }
+    let (payload, checksum) = decoded.split_at(decoded.len() - 4);
+    if payload[0] != 0x80 {
+        return Err(LegacyKeyError::WrongLength);
+    }
+
+    let single = Sha256::digest(payload);
+    let double = Sha256::digest(single);
+    if single[..4] != checksum[..4] && double[..4] != checksum[..4] {
+        return Err(LegacyKeyError::ChecksumMismatch);
+    }
+
+    let secret = &payload[1..];
+    Ok(format!("PVT_K1_{}", encode_with_k1_checksum(secret)))
+}
+
+fn encode_with_k1_checksum(raw: &[u8]) -> String {
+    let mut buf = raw.to_vec();
+    buf.extend_from_slice(K1_CHECKSUM_SUFFIX);
+    let checksum = ripemd160(&buf);
+
+    let mut data = raw.to_vec();
+    data.extend_from_slice(&checksum[..4]);
+    bs58::encode(data).into_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Both vectors below were generated by hand from raw bytes (not a real
+    // keypair) with the same base58/ripemd160 checksum rules this module
+    // implements, so the test is checking this code against an independent
+    // computation rather than against itself.
+
+    const LEGACY_PUB: &str = "EOS4tw6e36FfsDL3C8YZXGwhpPRYydrvZkSzYtmo7gMxG21UcLQTH";
+    const PUB_K1: &str = "PUB_K1_4tw6e36FfsDL3C8YZXGwhpPRYydrvZkSzYtmo7gMxG21Xa88m3";
+
+    const LEGACY_WIF: &str = "5HpjKrb7dH5kKQQzmbjB87Mxova7mek5bXUTWfndcX6tBoqUwzm";
+    const PVT_K1: &str = "PVT_K1_SkB92YpWm4Q2ijQHH34cqbKkCZWszsiQgHVjtNeFF2FtepK2";
+
+    #[test]
+    fn test_legacy_public_key_converts_to_the_equivalent_pub_k1_string() {
+        assert_eq!(legacy_public_key_to_pub_k1(LEGACY_PUB).unwrap(), PUB_K1);
+    }
+
+    #[test]
+    fn test_legacy_public_key_rejects_bad_checksum() {
+        let mut corrupted = LEGACY_PUB.to_string();
+        corrupted.replace_range(6..7, "1");
+        assert_eq!(
+            legacy_public_key_to_pub_k1(&corrupted),
+            Err(LegacyKeyError::ChecksumMismatch)
+        );
+    }
+
+    #[test]
+    fn test_legacy_wif_converts_to_the_equivalent_pvt_k1_string() {
+        assert_eq!(legacy_wif_to_pvt_k1(LEGACY_WIF).unwrap(), PVT_K1);
+    }
+
+    #[test]
+    fn test_legacy_wif_rejects_bad_checksum() {
+        let mut corrupted = LEGACY_WIF.to_string();
+        corrupted.replace_range(5..6, "1");
+        assert_eq!(
+            legacy_wif_to_pvt_k1(&corrupted),
+            Err(LegacyKeyError::ChecksumMismatch)
+        );
+    }
+}
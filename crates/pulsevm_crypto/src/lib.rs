@@ -1,5 +1,5 @@
 mod bytes;
-pub use bytes::Bytes;
+pub use bytes::{Bytes, BytesRef};
 
 mod digest;
 pub use digest::Digest;
@@ -7,5 +7,11 @@ pub use digest::Digest;
 mod fixed_bytes;
 pub use fixed_bytes::FixedBytes;
 
+mod hash_writer;
+pub use hash_writer::{hash_packed, HashWriter};
+
+mod legacy_key;
+pub use legacy_key::{legacy_public_key_to_pub_k1, legacy_wif_to_pvt_k1, LegacyKeyError};
+
 mod merkle_tree;
 pub use merkle_tree::merkle;
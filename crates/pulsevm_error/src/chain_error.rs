@@ -9,6 +9,13 @@ pub enum ChainError {
     InternalError(String),
     #[error("block error: {0}")]
     BlockError(String),
+    /// A block's `BlockTimestamp` failed `verify_block`'s check that it is
+    /// strictly after its parent's and not too far ahead of wall clock.
+    /// Carries both cases under one variant the same way `ApplyError`/
+    /// `WasmRuntimeError` do, since only the wall-clock-drift case depends
+    /// on this node's own clock; see `is_objective` below.
+    #[error("invalid block timestamp: {0}")]
+    InvalidBlockTimestamp(String),
     #[error("genesis error: {0}")]
     GenesisError(String),
     #[error("parse error: {0}")]
@@ -25,6 +32,13 @@ pub enum ChainError {
     NetworkError(String),
     #[error("wasm runtime error: {0}")]
     WasmRuntimeError(String),
+    /// A genuine WASM trap — `unreachable`, an out-of-bounds memory/table
+    /// access, integer division by zero, and the like — as opposed to a
+    /// contract calling `eosio_assert`/`pulse_assert` with `false`, which
+    /// stays an [`ChainError::ApplyError`]. Lets tooling tell a contract bug
+    /// apart from an intentional abort.
+    #[error("wasm trap: {0}")]
+    WasmTrap(String),
     #[error("apply error: {0}")]
     ApplyError(String),
     #[error("database error: {0}")]
@@ -39,6 +53,78 @@ pub enum ChainError {
     ActionValidationError(String),
     #[error("irrelevant authorization exception: {0}")]
     IrrelevantAuth(String),
+    #[error("transaction exceeded the current CPU usage limit imposed on the transaction: used {used} us, allowed {limit} us")]
+    TxCpuUsageExceeded { used: u64, limit: u64 },
+    #[error("transaction exceeded the current network usage limit imposed on the transaction: used {used} bytes, allowed {limit} bytes")]
+    TxNetUsageExceeded { used: u64, limit: u64 },
+    #[error("transaction CPU usage is too much for the remaining allowable usage of the current block: used {used} us, allowed {limit} us")]
+    BlockCpuUsageExceeded { used: u64, limit: u64 },
+    #[error("transaction network usage is too much for the remaining allowable usage of the current block: used {used} bytes, allowed {limit} bytes")]
+    BlockNetUsageExceeded { used: u64, limit: u64 },
+    #[error("account {account} has insufficient ram; needs {usage} bytes, has {limit} bytes")]
+    RamUsageExceeded {
+        account: String,
+        usage: i64,
+        limit: i64,
+    },
+    #[error(
+        "chain id mismatch: database was initialized with {expected}, but {found} was requested"
+    )]
+    ChainIdMismatch { expected: String, found: String },
+}
+
+impl ChainError {
+    /// True if this failure means the transaction or block is provably
+    /// wrong — bad authorization, a failed assertion, malformed data — the
+    /// same on every node, regardless of its local resources or timing.
+    /// False for failures that are node-specific, like running out of the
+    /// CPU budget this node happened to have available, or a local database
+    /// problem: a different node could easily have accepted the very same
+    /// transaction. `verify_block` uses this to decide whether a failure
+    /// while replaying an already-produced block means the block itself is
+    /// invalid, or just that this node couldn't confirm it.
+    pub fn is_objective(&self) -> bool {
+        match self {
+            ChainError::InternalError(_)
+            | ChainError::NetworkError(_)
+            | ChainError::DatabaseError(_) => false,
+            // These two variants carry both node-local resource exhaustion
+            // (CPU/deadline) and genuine contract failures (asserts, traps)
+            // under the same type, so fall back to the message until a
+            // dedicated exhaustion variant exists to tell them apart.
+            ChainError::ApplyError(msg) | ChainError::WasmRuntimeError(msg) => {
+                !msg.contains("exhausted") && !msg.contains("deadline")
+            }
+            // The non-increasing-timestamp case is the same on every node;
+            // the too-far-ahead-of-wall-clock case depends on this node's
+            // own clock, same reasoning as the CPU deadline case above.
+            ChainError::InvalidBlockTimestamp(msg) => !msg.contains("wall clock"),
+            ChainError::BlockError(_)
+            | ChainError::GenesisError(_)
+            | ChainError::ParseError(_)
+            | ChainError::AuthorizationError(_)
+            | ChainError::PermissionNotFound(_, _)
+            | ChainError::SignatureRecoverError(_)
+            | ChainError::TransactionError(_)
+            | ChainError::InvalidArgument(_)
+            | ChainError::SerializationError(_)
+            | ChainError::MissingAuthError(_)
+            | ChainError::ActionValidationError(_)
+            | ChainError::IrrelevantAuth(_)
+            | ChainError::WasmTrap(_)
+            // Each of these is derived from account limits and usage
+            // counters stored in chain state, so every node replaying the
+            // same blocks computes the same verdict - unlike the wall-clock
+            // CPU deadline case above, which depends on this node's own
+            // timing.
+            | ChainError::TxCpuUsageExceeded { .. }
+            | ChainError::TxNetUsageExceeded { .. }
+            | ChainError::BlockCpuUsageExceeded { .. }
+            | ChainError::BlockNetUsageExceeded { .. }
+            | ChainError::RamUsageExceeded { .. }
+            | ChainError::ChainIdMismatch { .. } => true,
+        }
+    }
 }
 
 impl From<Box<dyn Error>> for ChainError {
@@ -70,3 +156,68 @@ impl From<ChainError> for ErrorObjectOwned {
         ErrorObjectOwned::owned(-32000, err.to_string(), None::<()>)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_deadline_exhaustion_is_subjective() {
+        let err = ChainError::ApplyError("CPU limit of 1000 exhausted during apply".into());
+        assert!(!err.is_objective());
+    }
+
+    #[test]
+    fn test_failed_assertion_is_objective() {
+        let err = ChainError::ApplyError("eosio assert failed: nope".into());
+        assert!(err.is_objective());
+    }
+
+    #[test]
+    fn test_authorization_and_network_errors_are_objective_and_subjective_respectively() {
+        assert!(ChainError::AuthorizationError("missing signature".into()).is_objective());
+        assert!(!ChainError::NetworkError("peer disconnected".into()).is_objective());
+    }
+
+    #[test]
+    fn test_invalid_block_timestamp_is_objective_unless_it_is_the_wall_clock_drift_case() {
+        assert!(ChainError::InvalidBlockTimestamp(
+            "block timestamp is not strictly after parent".into()
+        )
+        .is_objective());
+        assert!(!ChainError::InvalidBlockTimestamp(
+            "block timestamp is too far ahead of wall clock".into()
+        )
+        .is_objective());
+    }
+
+    #[test]
+    fn test_resource_exhaustion_variants_are_objective() {
+        assert!(ChainError::TxCpuUsageExceeded {
+            used: 100,
+            limit: 50
+        }
+        .is_objective());
+        assert!(ChainError::TxNetUsageExceeded {
+            used: 100,
+            limit: 50
+        }
+        .is_objective());
+        assert!(ChainError::BlockCpuUsageExceeded {
+            used: 100,
+            limit: 50
+        }
+        .is_objective());
+        assert!(ChainError::BlockNetUsageExceeded {
+            used: 100,
+            limit: 50
+        }
+        .is_objective());
+        assert!(ChainError::RamUsageExceeded {
+            account: "alice".into(),
+            usage: 100,
+            limit: 50,
+        }
+        .is_objective());
+    }
+}
@@ -17,6 +17,16 @@ pub mod ffi {
         greylisted: bool,
     }
 
+    /// Current-window resource usage for a single account, as tracked by
+    /// the resource usage accumulators. `used`/`available`/`max` are all
+    /// `-1` when the account has no weight assigned for this resource
+    /// (i.e. it is unlimited for this resource).
+    struct AccountResourceUsage {
+        used: i64,
+        available: i64,
+        max: i64,
+    }
+
     struct Ratio {
         numerator: u64,
         denominator: u64,
@@ -259,7 +269,6 @@ pub mod ffi {
         pub fn get_payer(self: &IndexLongDoubleObject) -> &CxxName;
 
         // Methods on database
-        #[allow(dead_code)]
         pub fn flush(self: Pin<&mut Database>) -> Result<()>;
         pub fn undo(self: Pin<&mut Database>) -> Result<()>;
         pub fn commit(self: Pin<&mut Database>, revision: i64) -> Result<()>;
@@ -370,6 +379,16 @@ pub mod ffi {
             name: u64,
             greylist_limit: u32,
         ) -> Result<CpuLimitResult>;
+        pub fn get_account_net_usage(
+            self: &Database,
+            name: u64,
+            greylist_limit: u32,
+        ) -> Result<AccountResourceUsage>;
+        pub fn get_account_cpu_usage(
+            self: &Database,
+            name: u64,
+            greylist_limit: u32,
+        ) -> Result<AccountResourceUsage>;
         pub fn process_account_limit_updates(self: Pin<&mut Database>) -> Result<()>;
         pub fn set_block_parameters(
             self: Pin<&mut Database>,
@@ -1032,7 +1051,7 @@ pub mod ffi {
             ei: i32,
         ) -> Result<*const TableObject>;
         pub fn get(self: &CxxIndexDoubleIteratorCache, iterator: i32)
-        -> Result<&IndexDoubleObject>;
+            -> Result<&IndexDoubleObject>;
         pub fn remove(self: Pin<&mut CxxIndexDoubleIteratorCache>, iterator: i32) -> Result<()>;
         pub fn add(
             self: Pin<&mut CxxIndexDoubleIteratorCache>,
@@ -1062,7 +1081,7 @@ pub mod ffi {
             iterator: i32,
         ) -> Result<&IndexLongDoubleObject>;
         pub fn remove(self: Pin<&mut CxxIndexLongDoubleIteratorCache>, iterator: i32)
-        -> Result<()>;
+            -> Result<()>;
         pub fn add(
             self: Pin<&mut CxxIndexLongDoubleIteratorCache>,
             obj: &IndexLongDoubleObject,
@@ -1234,6 +1253,13 @@ pub mod ffi {
             reverse: bool,
             show_payer: bool,
         ) -> Result<String>;
+        pub fn get_kv_table_rows(
+            db: &Database,
+            code: u64,
+            scope: &str,
+            table: u64,
+            limit: u32,
+        ) -> Result<String>;
 
         // State history
         pub fn pack_deltas(self: &Database, full_snapshot: bool) -> Result<Vec<u8>>;
@@ -1295,3 +1321,14 @@ unsafe impl Sync for ffi::Database {}
 
 unsafe impl Send for ffi::UndoSession {}
 unsafe impl Sync for ffi::UndoSession {}
+
+// Safe for the same reason as `ffi::Database` above: all access goes through
+// `Database`'s own `RwLock`-guarded methods, so these chainbase row objects
+// are never read and written concurrently without synchronization. Needed so
+// a per-transaction cache of account lookups (see `TransactionContext`) can
+// hold on to them across calls.
+unsafe impl Send for ffi::AccountObject {}
+unsafe impl Sync for ffi::AccountObject {}
+
+unsafe impl Send for ffi::AccountMetadataObject {}
+unsafe impl Sync for ffi::AccountMetadataObject {}
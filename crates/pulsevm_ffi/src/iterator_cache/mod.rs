@@ -3,15 +3,15 @@ use std::{ops::Deref, pin::Pin};
 use pulsevm_error::ChainError;
 
 use crate::{
-    Index64Object, KeyValueObject, TableId, TableObject,
     bridge::ffi::{
-        CxxIndex64IteratorCache, CxxIndex128IteratorCache, CxxIndex256IteratorCache,
-        CxxIndexDoubleIteratorCache, CxxIndexLongDoubleIteratorCache, CxxKeyValueIteratorCache,
-        Index128Object, Index256Object, IndexDoubleObject, IndexLongDoubleObject,
+        new_index128_iterator_cache, new_index256_iterator_cache, new_index64_iterator_cache,
         new_index_double_iterator_cache, new_index_long_double_iterator_cache,
-        new_index64_iterator_cache, new_index128_iterator_cache, new_index256_iterator_cache,
-        new_key_value_iterator_cache,
+        new_key_value_iterator_cache, CxxIndex128IteratorCache, CxxIndex256IteratorCache,
+        CxxIndex64IteratorCache, CxxIndexDoubleIteratorCache, CxxIndexLongDoubleIteratorCache,
+        CxxKeyValueIteratorCache, Index128Object, Index256Object, IndexDoubleObject,
+        IndexLongDoubleObject,
     },
+    Index64Object, KeyValueObject, TableId, TableObject,
 };
 
 pub struct KeyValueIteratorCache {
@@ -65,6 +65,30 @@ impl KeyValueIteratorCache {
             .map_err(|e| ChainError::InternalError(format!("{}", e)))
     }
 
+    /// Like [`get`](Self::get), but also checks that `iterator`'s table was
+    /// created by `expected_code`. A stale or out-of-range iterator is
+    /// already rejected by `get` itself -- the C++ side bounds-checks with
+    /// `EOS_ASSERT`, which `cxx` turns into this `Err` rather than undefined
+    /// behavior -- but `get` alone doesn't know which contract is calling,
+    /// so a wrong-table iterator (one cached for a different contract's
+    /// table) would otherwise be handed back without complaint. Every
+    /// `db_*_update` host function in `ApplyContext` needs this same check,
+    /// so it lives here instead of being repeated at each call site.
+    pub fn get_checked(
+        &self,
+        iterator: i32,
+        expected_code: u64,
+    ) -> Result<&KeyValueObject, ChainError> {
+        let obj = self.get(iterator)?;
+        let table = self.get_table(obj.get_table_id())?;
+        if table.get_code().to_uint64_t() != expected_code {
+            return Err(ChainError::TransactionError(
+                "db access violation".to_string(),
+            ));
+        }
+        Ok(obj)
+    }
+
     pub fn remove(&mut self, iterator: i32) -> Result<(), ChainError> {
         self.inner
             .pin_mut()
@@ -142,6 +166,22 @@ impl Index64IteratorCache {
             .map_err(|e| ChainError::InternalError(format!("{}", e)))
     }
 
+    /// See [`KeyValueIteratorCache::get_checked`].
+    pub fn get_checked(
+        &self,
+        iterator: i32,
+        expected_code: u64,
+    ) -> Result<&Index64Object, ChainError> {
+        let obj = self.get(iterator)?;
+        let table = self.get_table(obj.get_table_id())?;
+        if table.get_code().to_uint64_t() != expected_code {
+            return Err(ChainError::TransactionError(
+                "db access violation".to_string(),
+            ));
+        }
+        Ok(obj)
+    }
+
     pub fn remove(&mut self, iterator: i32) -> Result<(), ChainError> {
         self.inner
             .pin_mut()
@@ -219,6 +259,22 @@ impl Index128IteratorCache {
             .map_err(|e| ChainError::InternalError(format!("{}", e)))
     }
 
+    /// See [`KeyValueIteratorCache::get_checked`].
+    pub fn get_checked(
+        &self,
+        iterator: i32,
+        expected_code: u64,
+    ) -> Result<&Index128Object, ChainError> {
+        let obj = self.get(iterator)?;
+        let table = self.get_table(obj.get_table_id())?;
+        if table.get_code().to_uint64_t() != expected_code {
+            return Err(ChainError::TransactionError(
+                "db access violation".to_string(),
+            ));
+        }
+        Ok(obj)
+    }
+
     pub fn remove(&mut self, iterator: i32) -> Result<(), ChainError> {
         self.inner
             .pin_mut()
@@ -296,6 +352,22 @@ impl Index256IteratorCache {
             .map_err(|e| ChainError::InternalError(format!("{}", e)))
     }
 
+    /// See [`KeyValueIteratorCache::get_checked`].
+    pub fn get_checked(
+        &self,
+        iterator: i32,
+        expected_code: u64,
+    ) -> Result<&Index256Object, ChainError> {
+        let obj = self.get(iterator)?;
+        let table = self.get_table(obj.get_table_id())?;
+        if table.get_code().to_uint64_t() != expected_code {
+            return Err(ChainError::TransactionError(
+                "db access violation".to_string(),
+            ));
+        }
+        Ok(obj)
+    }
+
     pub fn remove(&mut self, iterator: i32) -> Result<(), ChainError> {
         self.inner
             .pin_mut()
@@ -373,6 +445,22 @@ impl IndexDoubleIteratorCache {
             .map_err(|e| ChainError::InternalError(format!("{}", e)))
     }
 
+    /// See [`KeyValueIteratorCache::get_checked`].
+    pub fn get_checked(
+        &self,
+        iterator: i32,
+        expected_code: u64,
+    ) -> Result<&IndexDoubleObject, ChainError> {
+        let obj = self.get(iterator)?;
+        let table = self.get_table(obj.get_table_id())?;
+        if table.get_code().to_uint64_t() != expected_code {
+            return Err(ChainError::TransactionError(
+                "db access violation".to_string(),
+            ));
+        }
+        Ok(obj)
+    }
+
     pub fn remove(&mut self, iterator: i32) -> Result<(), ChainError> {
         self.inner
             .pin_mut()
@@ -450,6 +538,22 @@ impl IndexLongDoubleIteratorCache {
             .map_err(|e| ChainError::InternalError(format!("{}", e)))
     }
 
+    /// See [`KeyValueIteratorCache::get_checked`].
+    pub fn get_checked(
+        &self,
+        iterator: i32,
+        expected_code: u64,
+    ) -> Result<&IndexLongDoubleObject, ChainError> {
+        let obj = self.get(iterator)?;
+        let table = self.get_table(obj.get_table_id())?;
+        if table.get_code().to_uint64_t() != expected_code {
+            return Err(ChainError::TransactionError(
+                "db access violation".to_string(),
+            ));
+        }
+        Ok(obj)
+    }
+
     pub fn remove(&mut self, iterator: i32) -> Result<(), ChainError> {
         self.inner
             .pin_mut()
@@ -475,3 +579,74 @@ impl Deref for IndexLongDoubleIteratorCache {
 
 unsafe impl Send for IndexLongDoubleIteratorCache {}
 unsafe impl Sync for IndexLongDoubleIteratorCache {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Database;
+
+    use super::*;
+
+    fn open_temp_db() -> (tempfile::TempDir, Database) {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let mut db = Database::new(dir.path().to_str().unwrap(), 1024 * 1024 * 16)
+            .expect("failed to open database");
+        db.add_indices().unwrap();
+        (dir, db)
+    }
+
+    #[test]
+    fn test_get_checked_rejects_a_stale_iterator_after_remove() {
+        let (_dir, mut db) = open_temp_db();
+        let mut cache = KeyValueIteratorCache::new();
+        let table = db.create_table(1, 2, 3, 1).unwrap();
+        let table = unsafe { &*table };
+        let obj = db.create_key_value_object(table, 1, 42, b"hello").unwrap();
+        let obj = unsafe { &*obj };
+        cache.cache_table(table).unwrap();
+        let iterator = cache.add(obj).unwrap();
+
+        assert!(cache.get_checked(iterator, 1).is_ok());
+
+        cache.remove(iterator).unwrap();
+
+        assert!(matches!(
+            cache.get_checked(iterator, 1),
+            Err(ChainError::InternalError(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_checked_rejects_an_out_of_range_iterator() {
+        let cache = KeyValueIteratorCache::new();
+
+        assert!(matches!(
+            cache.get_checked(999, 1),
+            Err(ChainError::InternalError(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_checked_rejects_an_iterator_from_a_different_contracts_table() {
+        let (_dir, mut db) = open_temp_db();
+        let mut cache = KeyValueIteratorCache::new();
+
+        // Two tables owned by different contracts ("code"), sharing the
+        // same iterator cache instance -- the cache itself doesn't scope
+        // iterators by table, so `get` alone would happily hand back an
+        // object from either one.
+        let table_a = db.create_table(1, 2, 3, 1).unwrap();
+        let table_a = unsafe { &*table_a };
+        let obj_a = db
+            .create_key_value_object(table_a, 1, 1, b"from a")
+            .unwrap();
+        let obj_a = unsafe { &*obj_a };
+        cache.cache_table(table_a).unwrap();
+        let iterator_a = cache.add(obj_a).unwrap();
+
+        assert!(cache.get_checked(iterator_a, 1).is_ok());
+        assert!(matches!(
+            cache.get_checked(iterator_a, 99),
+            Err(ChainError::TransactionError(msg)) if msg == "db access violation"
+        ));
+    }
+}
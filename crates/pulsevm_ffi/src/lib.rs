@@ -10,7 +10,7 @@ pub use crate::bridge::ffi::{
     GlobalPropertyObject, Index64Object, Index128Object, Index256Object, IndexDoubleObject,
     IndexLongDoubleObject, KeyValueObject, KeyWeight, PermissionLevel, PermissionLevelWeight,
     PermissionLinkObject, PermissionObject, PermissionUsageObject, Ratio, TableId, TableObject,
-    WaitWeight,
+    UndoSession, WaitWeight,
 };
 pub use crate::bridge::ffi::{
     BlockTimestamp, ChainConfigV0, CxxBlockTimestamp, CxxChainConfig, CxxDigest, CxxGenesisState,
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use pulsevm_serialization::{NumBytes, Read, ReadError, Write, WriteError};
 use serde::{
@@ -43,6 +43,20 @@ impl BlockTimestamp {
         self.slot
     }
 
+    /// Rounds `now` down to the block interval and clamps it to strictly
+    /// after `parent`, so the result is both deterministic (pure integer
+    /// slot arithmetic, no wall-clock jitter) and monotonically increasing
+    /// regardless of how quickly blocks are produced.
+    #[inline]
+    pub fn round_to_interval_after(now: TimePoint, parent: BlockTimestamp) -> Self {
+        let rounded: BlockTimestamp = now.into();
+        if rounded.slot() > parent.slot() {
+            rounded
+        } else {
+            parent.next()
+        }
+    }
+
     pub fn to_eos_string(&self) -> String {
         // total ms since Unix epoch
         let total_ms = (self.slot() as i64) * (Self::BLOCK_INTERVAL_MS as i64)
@@ -149,45 +163,11 @@ impl<'de> Deserialize<'de> for BlockTimestamp {
                 f.write_str(r#"an EOS block timestamp like "YYYY-MM-DDTHH:MM:SS.sss" (optionally with a trailing 'Z')"#)
             }
 
-            fn visit_str<E>(self, mut v: &str) -> Result<Self::Value, E>
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
             where
                 E: de::Error,
             {
-                // Allow optional trailing Z
-                if let Some(stripped) = v.strip_suffix('Z') {
-                    v = stripped;
-                }
-
-                // Try with milliseconds first, then without (assume .000)
-                const FMT_MS: &[time::format_description::FormatItem<'_>] = format_description!(
-                    "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]"
-                );
-                const FMT_SEC: &[time::format_description::FormatItem<'_>] =
-                    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
-
-                let pdt = PrimitiveDateTime::parse(v, FMT_MS)
-                    .or_else(|_| PrimitiveDateTime::parse(v, FMT_SEC))
-                    .map_err(|e| E::custom(format!("invalid block timestamp: {e}")))?;
-
-                let odt = pdt.assume_utc();
-                let total_ms = odt
-                    .unix_timestamp()
-                    .saturating_mul(1000)
-                    .saturating_add((odt.nanosecond() / 1_000_000) as i64);
-
-                // Convert to EOS slot (500 ms from 2000-01-01T00:00:00Z)
-                let delta = total_ms - BlockTimestamp::BLOCK_TIMESTAMP_EPOCH_MS;
-                if delta < 0 {
-                    return Err(E::custom(
-                        "timestamp before EOS block timestamp epoch (2000-01-01T00:00:00Z)",
-                    ));
-                }
-                if delta % (BlockTimestamp::BLOCK_INTERVAL_MS as i64) != 0 {
-                    return Err(E::custom("timestamp not aligned to 500ms boundary"));
-                }
-                let slot = (delta / (BlockTimestamp::BLOCK_INTERVAL_MS as i64)) as u32;
-
-                Ok(BlockTimestamp::new(slot))
+                parse_eos_block_timestamp(v).map_err(E::custom)
             }
         }
 
@@ -195,6 +175,52 @@ impl<'de> Deserialize<'de> for BlockTimestamp {
     }
 }
 
+/// Shared by [`Deserialize`] and [`FromStr`]: parses an EOS block timestamp
+/// string like `"2000-01-01T00:00:00.500"` (optionally with a trailing `Z`)
+/// into its 500ms slot since [`BlockTimestamp::BLOCK_TIMESTAMP_EPOCH_MS`].
+fn parse_eos_block_timestamp(v: &str) -> Result<BlockTimestamp, String> {
+    // Allow optional trailing Z
+    let v = v.strip_suffix('Z').unwrap_or(v);
+
+    // Try with milliseconds first, then without (assume .000)
+    const FMT_MS: &[time::format_description::FormatItem<'_>] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]");
+    const FMT_SEC: &[time::format_description::FormatItem<'_>] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+
+    let pdt = PrimitiveDateTime::parse(v, FMT_MS)
+        .or_else(|_| PrimitiveDateTime::parse(v, FMT_SEC))
+        .map_err(|e| format!("invalid block timestamp: {e}"))?;
+
+    let odt = pdt.assume_utc();
+    let total_ms = odt
+        .unix_timestamp()
+        .saturating_mul(1000)
+        .saturating_add((odt.nanosecond() / 1_000_000) as i64);
+
+    // Convert to EOS slot (500 ms from 2000-01-01T00:00:00Z)
+    let delta = total_ms - BlockTimestamp::BLOCK_TIMESTAMP_EPOCH_MS;
+    if delta < 0 {
+        return Err(
+            "timestamp before EOS block timestamp epoch (2000-01-01T00:00:00Z)".to_string(),
+        );
+    }
+    if delta % (BlockTimestamp::BLOCK_INTERVAL_MS as i64) != 0 {
+        return Err("timestamp not aligned to 500ms boundary".to_string());
+    }
+    let slot = (delta / (BlockTimestamp::BLOCK_INTERVAL_MS as i64)) as u32;
+
+    Ok(BlockTimestamp::new(slot))
+}
+
+impl FromStr for BlockTimestamp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_eos_block_timestamp(s)
+    }
+}
+
 impl NumBytes for BlockTimestamp {
     fn num_bytes(&self) -> usize {
         4
@@ -213,3 +239,54 @@ impl Write for BlockTimestamp {
         self.slot().write(bytes, pos)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Microseconds;
+
+    fn time_point_for_slot(slot: u32) -> TimePoint {
+        let msec = (slot as i64) * (BlockTimestamp::BLOCK_INTERVAL_MS as i64)
+            + BlockTimestamp::BLOCK_TIMESTAMP_EPOCH_MS;
+        TimePoint::new(Microseconds::new(msec * 1000))
+    }
+
+    #[test]
+    fn test_round_to_interval_after_advances_when_now_is_ahead() {
+        let parent = BlockTimestamp::new(10);
+        let now = time_point_for_slot(12);
+        assert_eq!(
+            BlockTimestamp::round_to_interval_after(now, parent).slot(),
+            12
+        );
+    }
+
+    #[test]
+    fn test_round_to_interval_after_clamps_to_next_slot_when_now_has_not_advanced() {
+        let parent = BlockTimestamp::new(10);
+        let now = time_point_for_slot(10);
+        assert_eq!(
+            BlockTimestamp::round_to_interval_after(now, parent).slot(),
+            11
+        );
+
+        // Clocks that drift backwards still produce a strictly increasing slot.
+        let stale_now = time_point_for_slot(5);
+        assert_eq!(
+            BlockTimestamp::round_to_interval_after(stale_now, parent).slot(),
+            11
+        );
+    }
+
+    #[test]
+    fn test_to_eos_string_round_trips_through_from_str() {
+        let ts = BlockTimestamp::new(12345);
+        let s = ts.to_eos_string();
+        assert_eq!(s.parse::<BlockTimestamp>().unwrap(), ts);
+    }
+
+    #[test]
+    fn test_from_str_rejects_misaligned_timestamps() {
+        assert!("2000-01-01T00:00:00.100".parse::<BlockTimestamp>().is_err());
+    }
+}
@@ -10,7 +10,7 @@ use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
     de::{self, Visitor},
 };
-use time::{OffsetDateTime, PrimitiveDateTime, macros::format_description};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, macros::format_description};
 
 use crate::{
     CxxTimePoint,
@@ -180,10 +180,13 @@ impl TimePoint {
         TimePoint::new(Microseconds::new(micros_i64))
     }
 
-    /// Exact EOS-style string: "YYYY-MM-DDTHH:MM:SSZ"
+    /// Exact EOS-style string: "YYYY-MM-DDTHH:MM:SS.sssZ"
     pub fn to_eos_string(&self) -> String {
-        let dt = OffsetDateTime::from_unix_timestamp(self.sec_since_epoch() as i64)
-            .expect("valid unix timestamp");
+        let micros = self.elapsed.count();
+        let secs = micros.div_euclid(1_000_000);
+        let rem_micros = micros.rem_euclid(1_000_000);
+        let dt = OffsetDateTime::from_unix_timestamp(secs).expect("valid unix timestamp")
+            + Duration::microseconds(rem_micros);
         dt.format(EOS_FMT_MILLIS_Z).expect("formatting never fails")
     }
 }
@@ -356,4 +359,33 @@ mod tests {
         let time_point: TimePoint = serde_json::from_str(serialized).unwrap();
         assert_eq!(time_point.sec_since_epoch(), 0);
     }
+
+    #[test]
+    fn test_time_point_to_time_point_sec_roundtrip() {
+        use crate::bridge::ffi::TimePointSec;
+
+        let tp = TimePoint::new(seconds(1_700_000_000));
+        let tps: TimePointSec = tp.into();
+        let back: TimePoint = tps.into();
+        assert_eq!(tps.sec_since_epoch(), 1_700_000_000);
+        assert_eq!(back, tp);
+    }
+
+    #[test]
+    fn test_time_point_to_eos_string_preserves_fractional_seconds() {
+        let tp = TimePoint::new(Microseconds::new(1_700_000_000_500_000));
+        assert_eq!(tp.to_eos_string(), "2023-11-14T22:13:20.500Z");
+        assert_eq!(tp.to_string().parse::<TimePoint>().unwrap(), tp);
+    }
+
+    #[test]
+    fn test_time_point_plus_block_interval_matches_next_slot() {
+        use crate::bridge::ffi::BlockTimestamp;
+
+        let now = TimePoint::now();
+        let current: BlockTimestamp = now.into();
+        let next = now + milliseconds(BlockTimestamp::BLOCK_INTERVAL_MS as i64);
+        let next_slot: BlockTimestamp = next.into();
+        assert_eq!(next_slot.slot(), current.slot() + 1);
+    }
 }
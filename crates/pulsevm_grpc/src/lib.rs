@@ -1,4 +1,4 @@
-mod io {
+pub mod io {
     pub mod prometheus {
         pub mod client {
             tonic::include_proto!("io.prometheus.client");
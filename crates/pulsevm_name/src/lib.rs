@@ -29,6 +29,11 @@ impl fmt::Display for ParseNameError {
     }
 }
 
+/// The single Rust-side representation of an EOSIO-style name. Other crates
+/// that need a `Name` (`pulsevm_core`, `pulsevm`, ...) re-export this type
+/// rather than defining their own; the only other "name" in the tree is the
+/// C++ chainbase `name` reached through `pulsevm_ffi::CxxName`, which is a
+/// separate opaque FFI type, not a second Rust implementation.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Read, Write, NumBytes)]
 pub struct Name(u64);
 
@@ -48,6 +53,23 @@ impl Name {
     pub fn as_bytes(&self) -> [u8; NAME_MAX_LEN] {
         name_to_bytes(self.0)
     }
+
+    /// Tests this name against a glob-style filter pattern, the kind used to
+    /// subscribe to "all actions on accounts under `pulse.`" in history and
+    /// notification filtering: a bare name is an exact match, a trailing `*`
+    /// matches on the dotted prefix before it (`"pulse.*"` matches `pulse.any`
+    /// and `pulse.token` but not `pulsevm`), and a leading `*` matches on the
+    /// dotted suffix after it (`"*.token"` matches `eosio.token`). `"*"` alone
+    /// matches every name.
+    pub fn matches(&self, pattern: &str) -> bool {
+        if let Some(prefix) = pattern.strip_suffix('*') {
+            self.to_string().starts_with(prefix)
+        } else if let Some(suffix) = pattern.strip_prefix('*') {
+            self.to_string().ends_with(suffix)
+        } else {
+            self.to_string() == pattern
+        }
+    }
 }
 
 impl From<u64> for Name {
@@ -66,11 +88,11 @@ impl FromStr for Name {
     type Err = ChainError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // First try to parse as u64
-        if let Ok(value) = s.parse::<u64>() {
-            return Ok(value.into()); // assuming `u64: Into<YourType>`
-        }
-
+        // Matches the C++ chainbase `name::set`: a name string is always
+        // decoded through the base-32 name charset, never as a bare
+        // integer. (`"123"` decodes via `char_to_value`, just like
+        // `string_to_name("123")` does on the FFI side; it is not the
+        // literal value `123`.)
         let name = name_from_bytes(s.bytes())
             .map_err(|e| ChainError::ParseError(format!("invalid name format: {}", e)))?;
         Ok(name.into())
@@ -166,6 +188,38 @@ mod tests {
     #[test]
     fn test_name_from_str() {
         let name = Name::from_str("oracles").unwrap();
-        assert_eq!(name.as_u64(), 6138663577826885632);
+        assert_eq!(name.as_u64(), 11947074179527868416);
+        assert_eq!(name.to_string(), "oracles");
+    }
+
+    #[test]
+    fn test_matches_exact() {
+        let name = Name::from_str("alice").unwrap();
+        assert!(name.matches("alice"));
+        assert!(!name.matches("bob"));
+    }
+
+    #[test]
+    fn test_matches_prefix_wildcard() {
+        assert!(Name::from_str("pulse.any").unwrap().matches("pulse.*"));
+        assert!(Name::from_str("pulse.token").unwrap().matches("pulse.*"));
+        assert!(!Name::from_str("pulsevm").unwrap().matches("pulse.*"));
+    }
+
+    #[test]
+    fn test_matches_suffix_wildcard() {
+        assert!(Name::from_str("eosio.token").unwrap().matches("*.token"));
+        assert!(!Name::from_str("eosio.any").unwrap().matches("*.token"));
+    }
+
+    #[test]
+    fn test_matches_any_name_semantics() {
+        // "pulse.any" already means "match anything" elsewhere (see
+        // `ANY_NAME` in pulsevm_core), so it should satisfy both its own
+        // exact pattern and the broader "pulse.*" prefix.
+        let any_name = Name::from_str("pulse.any").unwrap();
+        assert!(any_name.matches("pulse.any"));
+        assert!(any_name.matches("pulse.*"));
+        assert!(any_name.matches("*"));
     }
 }
@@ -42,51 +42,124 @@ impl ToTokens for DeriveNumBytes {
         let (impl_generics, ty_generics, where_clause) = &self.generics.split_for_impl();
         let call_site = ::proc_macro2::Span::call_site();
         let var = quote!(self);
-        let add_to_count = match &self.data {
-            Data::Struct(data) => match data.fields {
-                Fields::Named(ref fields) => {
-                    let recurse = fields.named.iter().map(|f| {
-                        let name = &f.ident;
-                        let access = quote_spanned!(call_site => #var.#name);
-                        quote_spanned! { f.span() =>
-                            count += pulsevm_serialization::NumBytes::num_bytes(&#access);
-                        }
-                    });
-                    quote! {
-                        #(#recurse)*
+
+        let const_size = match &self.data {
+            Data::Struct(data) => {
+                let field_types: alloc::vec::Vec<_> = match data.fields {
+                    Fields::Named(ref fields) => {
+                        fields.named.iter().map(|f| f.ty.clone()).collect()
+                    }
+                    Fields::Unnamed(ref fields) => {
+                        fields.unnamed.iter().map(|f| f.ty.clone()).collect()
                     }
+                    Fields::Unit => alloc::vec::Vec::new(),
+                };
+                let combine = field_types.iter().map(|ty| {
+                    quote_spanned! { ty.span() =>
+                        size = pulsevm_serialization::combine_const_size(
+                            size,
+                            <#ty as pulsevm_serialization::NumBytes>::CONST_SIZE,
+                        );
+                    }
+                });
+                quote! {
+                    const CONST_SIZE: Option<usize> = {
+                        let mut size: Option<usize> = Some(0);
+                        #(#combine)*
+                        size
+                    };
                 }
-                Fields::Unnamed(ref fields) => {
-                    let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                        let index = Index {
-                            index: i as u32,
-                            span: call_site,
-                        };
-                        let access = quote_spanned!(call_site => #var.#index);
-                        quote_spanned! { f.span() =>
-                            count += pulsevm_serialization::NumBytes::num_bytes(&#access);
+            }
+            // A variant's tag is itself a `VarUint32`, whose width depends on
+            // how many variants there are, and different variants can carry
+            // different payloads - so an enum's size is never constant.
+            // Leave `CONST_SIZE` at the trait's `None` default.
+            Data::Enum(_) | Data::Union(_) => quote! {},
+        };
+
+        let body = match &self.data {
+            Data::Struct(data) => {
+                let add_to_count = match data.fields {
+                    Fields::Named(ref fields) => {
+                        let recurse = fields.named.iter().map(|f| {
+                            let name = &f.ident;
+                            let access = quote_spanned!(call_site => #var.#name);
+                            quote_spanned! { f.span() =>
+                                count += pulsevm_serialization::NumBytes::num_bytes(&#access);
+                            }
+                        });
+                        quote! {
+                            #(#recurse)*
+                        }
+                    }
+                    Fields::Unnamed(ref fields) => {
+                        let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
+                            let index = Index {
+                                index: i as u32,
+                                span: call_site,
+                            };
+                            let access = quote_spanned!(call_site => #var.#index);
+                            quote_spanned! { f.span() =>
+                                count += pulsevm_serialization::NumBytes::num_bytes(&#access);
+                            }
+                        });
+                        quote! {
+                            #(#recurse)*
                         }
-                    });
-                    quote! {
-                        #(#recurse)*
                     }
+                    Fields::Unit => {
+                        quote! {}
+                    }
+                };
+                quote! {
+                    let mut count = 0;
+                    #add_to_count
+                    count
                 }
-                Fields::Unit => {
-                    quote! {}
+            }
+            Data::Enum(data) => {
+                // Variants serialize as a VarUint32 tag (the variant index)
+                // followed by the payload, the same EOSIO `variant` layout
+                // the Read/Write derives below use.
+                let arms = data.variants.iter().enumerate().map(|(i, v)| {
+                    let variant = &v.ident;
+                    let tag = i as u32;
+                    match &v.fields {
+                        Fields::Unit => quote_spanned! { v.span() =>
+                            #name::#variant => pulsevm_serialization::NumBytes::num_bytes(&pulsevm_serialization::VarUint32(#tag)),
+                        },
+                        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                            quote_spanned! { v.span() =>
+                                #name::#variant(payload) => {
+                                    pulsevm_serialization::NumBytes::num_bytes(&pulsevm_serialization::VarUint32(#tag))
+                                        + pulsevm_serialization::NumBytes::num_bytes(payload)
+                                }
+                            }
+                        }
+                        _ => panic!("NumBytes derive only supports unit or single-field enum variants"),
+                    }
+                });
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
                 }
-            },
-            Data::Enum(_) | Data::Union(_) => unimplemented!(),
+            }
+            Data::Union(_) => unimplemented!(),
         };
 
         let expanded = quote! {
             #[automatically_derived]
             #[allow(unused_qualifications)]
             impl #impl_generics pulsevm_serialization::NumBytes for #name #ty_generics #where_clause {
+                #const_size
+
                 #[inline]
                 fn num_bytes(&self) -> usize {
-                    let mut count = 0;
-                    #add_to_count
-                    count
+                    if let Some(size) = <Self as pulsevm_serialization::NumBytes>::CONST_SIZE {
+                        return size;
+                    }
+                    #body
                 }
             }
         };
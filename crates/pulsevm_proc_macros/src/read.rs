@@ -94,7 +94,34 @@ impl ToTokens for DeriveRead {
                     unimplemented!();
                 }
             },
-            Data::Enum(_) | Data::Union(_) => unimplemented!(),
+            Data::Enum(data) => {
+                // Mirrors the Write derive's variant layout: a VarUint32 tag
+                // selects the variant, then its payload (if any) follows.
+                let arms = data.variants.iter().enumerate().map(|(i, v)| {
+                    let variant = &v.ident;
+                    let tag = i as u32;
+                    match &v.fields {
+                        Fields::Unit => quote_spanned! { v.span() =>
+                            #tag => Ok(#name::#variant),
+                        },
+                        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                            let ty = &fields.unnamed.first().unwrap().ty;
+                            quote_spanned! { v.span() =>
+                                #tag => Ok(#name::#variant(<#ty as pulsevm_serialization::Read>::read(bytes, pos)?)),
+                            }
+                        }
+                        _ => panic!("Read derive only supports unit or single-field enum variants"),
+                    }
+                });
+                quote! {
+                    let tag = <pulsevm_serialization::VarUint32 as pulsevm_serialization::Read>::read(bytes, pos)?;
+                    match tag.0 {
+                        #(#arms)*
+                        _ => Err(pulsevm_serialization::ReadError::ParseError),
+                    }
+                }
+            }
+            Data::Union(_) => unimplemented!(),
         };
 
         let expanded = quote! {
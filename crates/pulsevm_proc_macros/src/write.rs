@@ -79,7 +79,37 @@ impl ToTokens for DeriveWrite {
                     }
                 }
             },
-            Data::Enum(_) | Data::Union(_) => unimplemented!(),
+            Data::Enum(data) => {
+                // Mirrors the NumBytes derive's variant layout: a VarUint32
+                // tag (the variant index) followed by the payload, if any.
+                let arms = data.variants.iter().enumerate().map(|(i, v)| {
+                    let variant = &v.ident;
+                    let tag = i as u32;
+                    match &v.fields {
+                        Fields::Unit => quote_spanned! { v.span() =>
+                            #name::#variant => {
+                                pulsevm_serialization::Write::write(&pulsevm_serialization::VarUint32(#tag), bytes, pos)?;
+                            }
+                        },
+                        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                            quote_spanned! { v.span() =>
+                                #name::#variant(payload) => {
+                                    pulsevm_serialization::Write::write(&pulsevm_serialization::VarUint32(#tag), bytes, pos)?;
+                                    pulsevm_serialization::Write::write(payload, bytes, pos)?;
+                                }
+                            }
+                        }
+                        _ => panic!("Write derive only supports unit or single-field enum variants"),
+                    }
+                });
+                quote! {
+                    match #var {
+                        #(#arms)*
+                    }
+                    Ok(())
+                }
+            }
+            Data::Union(_) => unimplemented!(),
         };
 
         let expanded = quote! {
@@ -0,0 +1,10 @@
+pub const PULSE_BIOS_WASM: &[u8] = include_bytes!("../../../reference_contracts/pulse_bios.wasm");
+pub const PULSE_TOKEN_WASM: &[u8] = include_bytes!("../../../reference_contracts/pulse_token.wasm");
+
+pub fn pulse_bios_wasm() -> &'static [u8] {
+    PULSE_BIOS_WASM
+}
+
+pub fn pulse_token_wasm() -> &'static [u8] {
+    PULSE_TOKEN_WASM
+}
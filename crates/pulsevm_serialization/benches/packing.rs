@@ -0,0 +1,35 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use pulsevm_proc_macros::{NumBytes, Write as WriteDerive};
+use pulsevm_serialization::Write;
+use std::hint::black_box;
+
+/// A fixed-size struct shaped like an action header, so `CONST_SIZE` lets
+/// `num_bytes()` skip the per-field recursion entirely.
+#[derive(Clone, Copy, NumBytes, WriteDerive)]
+struct FixedSizeAction {
+    account: u64,
+    name: u64,
+    authorization: u64,
+    sequence: u32,
+}
+
+fn bench(value: &FixedSizeAction) {
+    for _ in 0..1_000_000 {
+        let _ = black_box(value).pack().unwrap();
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let value = FixedSizeAction {
+        account: 1,
+        name: 2,
+        authorization: 3,
+        sequence: 4,
+    };
+    c.bench_function("pack fixed-size action 1_000_000 times", |b| {
+        b.iter(|| bench(black_box(&value)))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
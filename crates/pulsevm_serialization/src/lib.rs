@@ -8,10 +8,29 @@ pub use varint::*;
 mod primitives;
 
 pub trait NumBytes {
+    /// The encoded size of this type when it doesn't depend on the value -
+    /// fixed-size primitives, and structs made entirely of such fields.
+    /// `num_bytes()` should return this directly instead of recursing
+    /// through fields when it's set. `None` means the size is
+    /// value-dependent (strings, vecs, anything that embeds one of those).
+    const CONST_SIZE: Option<usize> = None;
+
     /// Count the number of bytes a type is expected to use.
     fn num_bytes(&self) -> usize;
 }
 
+/// Combines two fields' constant sizes, short-circuiting to `None` as soon
+/// as either one isn't itself constant - the compile-time analogue of the
+/// recursion `num_bytes()` falls back to at runtime. Used by the `NumBytes`
+/// derive to compute a struct's own `CONST_SIZE` from its fields'.
+#[doc(hidden)]
+pub const fn combine_const_size(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        _ => None,
+    }
+}
+
 /// Error that can be returned when writing bytes.
 #[derive(Debug, Clone)]
 pub enum WriteError {
@@ -108,3 +127,13 @@ impl From<ReadError> for ChainError {
 pub trait Read: Sized + NumBytes {
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError>;
 }
+
+/// Like [`Read`], but returns a value borrowed from `bytes` instead of an
+/// owned copy. Only worth implementing for byte-slice fields - reading a
+/// large contract code blob out of a transaction this way lets the caller
+/// look at the bytes (hash them, validate them) without copying them, right
+/// up until they actually need to be stored somewhere that outlives `bytes`.
+/// Everything else should keep using [`Read`].
+pub trait ReadRef<'a>: Sized {
+    fn read_ref(bytes: &'a [u8], pos: &mut usize) -> Result<Self, ReadError>;
+}
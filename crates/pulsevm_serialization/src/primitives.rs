@@ -1,10 +1,18 @@
+//! `Read`/`Write`/`NumBytes` impls for the standard library types used on
+//! the wire. All multi-byte integers are packed little-endian here, matching
+//! EOSIO's wire format; this is load-bearing for signing digests and action
+//! data, so don't change it per-type. Code that needs a byte-ordered range
+//! scan (chainbase secondary indices keyed on block height, for example)
+//! intentionally encodes those keys big-endian instead, outside of these
+//! `Read`/`Write` impls, with a comment at the call site.
+
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     hash::Hash,
     sync::Arc,
 };
 
-use crate::{NumBytes, Read, ReadError, VarUint32, Write, WriteError};
+use crate::{NumBytes, Read, ReadError, ReadRef, VarUint32, Write, WriteError};
 
 #[inline]
 fn take<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], ReadError> {
@@ -16,6 +24,18 @@ fn take<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], ReadError> {
     Ok(head.try_into().unwrap())
 }
 
+/// Rejects a claimed element count before it's used to pre-allocate, so a
+/// crafted length prefix can't make us reserve gigabytes of memory before any
+/// element has actually been validated. Every element needs at least one
+/// byte, so `len` can never exceed the number of bytes left in the buffer.
+#[inline]
+fn check_claimed_len(len: usize, bytes: &[u8], pos: usize) -> Result<(), ReadError> {
+    if len > bytes.len().saturating_sub(pos) {
+        return Err(ReadError::NotEnoughBytes);
+    }
+    Ok(())
+}
+
 impl NumBytes for usize {
     #[inline]
     fn num_bytes(&self) -> usize {
@@ -24,6 +44,8 @@ impl NumBytes for usize {
 }
 
 impl NumBytes for u8 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u8>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u8>()
@@ -31,6 +53,8 @@ impl NumBytes for u8 {
 }
 
 impl NumBytes for i8 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u8>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u8>()
@@ -38,6 +62,8 @@ impl NumBytes for i8 {
 }
 
 impl NumBytes for u16 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u16>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u16>()
@@ -45,6 +71,8 @@ impl NumBytes for u16 {
 }
 
 impl NumBytes for i16 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u16>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u16>()
@@ -52,6 +80,8 @@ impl NumBytes for i16 {
 }
 
 impl NumBytes for u32 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u32>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u32>()
@@ -59,6 +89,8 @@ impl NumBytes for u32 {
 }
 
 impl NumBytes for i32 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u32>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u32>()
@@ -66,6 +98,8 @@ impl NumBytes for i32 {
 }
 
 impl NumBytes for u64 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u64>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u64>()
@@ -73,13 +107,26 @@ impl NumBytes for u64 {
 }
 
 impl NumBytes for i64 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u64>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u64>()
     }
 }
 
+impl NumBytes for u128 {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u128>());
+
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        core::mem::size_of::<u128>()
+    }
+}
+
 impl NumBytes for f32 {
+    const CONST_SIZE: Option<usize> = Some(4);
+
     #[inline]
     fn num_bytes(&self) -> usize {
         4
@@ -87,6 +134,8 @@ impl NumBytes for f32 {
 }
 
 impl NumBytes for f64 {
+    const CONST_SIZE: Option<usize> = Some(8);
+
     #[inline]
     fn num_bytes(&self) -> usize {
         8
@@ -101,6 +150,8 @@ impl NumBytes for String {
 }
 
 impl NumBytes for bool {
+    const CONST_SIZE: Option<usize> = Some(core::mem::size_of::<u8>());
+
     #[inline]
     fn num_bytes(&self) -> usize {
         core::mem::size_of::<u8>()
@@ -167,7 +218,21 @@ impl<K: Write + NumBytes, V: Write + NumBytes> NumBytes for HashMap<K, V> {
     }
 }
 
+impl<T: NumBytes> NumBytes for HashSet<T> {
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        self.len().num_bytes() + self.iter().map(NumBytes::num_bytes).sum::<usize>()
+    }
+}
+
+// Tuples of up to 6 elements get `NumBytes`/`Read`/`Write` below, so packing
+// several heterogeneous values (say a `Name`, an `Asset`, and a `String`)
+// together is just `(name, asset, memo).pack()`: `num_bytes()` sums the
+// elements up front, so `pack()` still allocates the output buffer exactly
+// once, the same as packing a single struct with that many fields.
 impl<T1: NumBytes, T2: NumBytes> NumBytes for (T1, T2) {
+    const CONST_SIZE: Option<usize> = crate::combine_const_size(T1::CONST_SIZE, T2::CONST_SIZE);
+
     #[inline]
     fn num_bytes(&self) -> usize {
         self.0.num_bytes() + self.1.num_bytes()
@@ -175,6 +240,11 @@ impl<T1: NumBytes, T2: NumBytes> NumBytes for (T1, T2) {
 }
 
 impl<T1: NumBytes, T2: NumBytes, T3: NumBytes> NumBytes for (T1, T2, T3) {
+    const CONST_SIZE: Option<usize> = crate::combine_const_size(
+        crate::combine_const_size(T1::CONST_SIZE, T2::CONST_SIZE),
+        T3::CONST_SIZE,
+    );
+
     #[inline]
     fn num_bytes(&self) -> usize {
         self.0.num_bytes() + self.1.num_bytes() + self.2.num_bytes()
@@ -182,12 +252,72 @@ impl<T1: NumBytes, T2: NumBytes, T3: NumBytes> NumBytes for (T1, T2, T3) {
 }
 
 impl<T1: NumBytes, T2: NumBytes, T3: NumBytes, T4: NumBytes> NumBytes for (T1, T2, T3, T4) {
+    const CONST_SIZE: Option<usize> = crate::combine_const_size(
+        crate::combine_const_size(
+            crate::combine_const_size(T1::CONST_SIZE, T2::CONST_SIZE),
+            T3::CONST_SIZE,
+        ),
+        T4::CONST_SIZE,
+    );
+
     #[inline]
     fn num_bytes(&self) -> usize {
         self.0.num_bytes() + self.1.num_bytes() + self.2.num_bytes() + self.3.num_bytes()
     }
 }
 
+impl<T1: NumBytes, T2: NumBytes, T3: NumBytes, T4: NumBytes, T5: NumBytes> NumBytes
+    for (T1, T2, T3, T4, T5)
+{
+    const CONST_SIZE: Option<usize> = crate::combine_const_size(
+        crate::combine_const_size(
+            crate::combine_const_size(
+                crate::combine_const_size(T1::CONST_SIZE, T2::CONST_SIZE),
+                T3::CONST_SIZE,
+            ),
+            T4::CONST_SIZE,
+        ),
+        T5::CONST_SIZE,
+    );
+
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        self.0.num_bytes()
+            + self.1.num_bytes()
+            + self.2.num_bytes()
+            + self.3.num_bytes()
+            + self.4.num_bytes()
+    }
+}
+
+impl<T1: NumBytes, T2: NumBytes, T3: NumBytes, T4: NumBytes, T5: NumBytes, T6: NumBytes> NumBytes
+    for (T1, T2, T3, T4, T5, T6)
+{
+    const CONST_SIZE: Option<usize> = crate::combine_const_size(
+        crate::combine_const_size(
+            crate::combine_const_size(
+                crate::combine_const_size(
+                    crate::combine_const_size(T1::CONST_SIZE, T2::CONST_SIZE),
+                    T3::CONST_SIZE,
+                ),
+                T4::CONST_SIZE,
+            ),
+            T5::CONST_SIZE,
+        ),
+        T6::CONST_SIZE,
+    );
+
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        self.0.num_bytes()
+            + self.1.num_bytes()
+            + self.2.num_bytes()
+            + self.3.num_bytes()
+            + self.4.num_bytes()
+            + self.5.num_bytes()
+    }
+}
+
 impl<T: NumBytes> NumBytes for Arc<T> {
     #[inline]
     fn num_bytes(&self) -> usize {
@@ -278,6 +408,16 @@ impl Read for i64 {
     }
 }
 
+impl Read for u128 {
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let mut b = &bytes[*pos..];
+        let arr = take::<16>(&mut b)?;
+        *pos += 16;
+        Ok(u128::from_le_bytes(arr))
+    }
+}
+
 impl Read for f32 {
     #[inline]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
@@ -322,6 +462,7 @@ where
     #[inline]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
         let len = usize::read(bytes, pos)?;
+        check_claimed_len(len, bytes, *pos)?;
         let mut vec = Vec::with_capacity(len);
         for _ in 0..len {
             let item = T::read(bytes, pos)?;
@@ -331,6 +472,20 @@ where
     }
 }
 
+impl<'a> ReadRef<'a> for &'a [u8] {
+    #[inline]
+    fn read_ref(bytes: &'a [u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let len = usize::read(bytes, pos)?;
+        check_claimed_len(len, bytes, *pos)?;
+
+        let start = *pos;
+        let end = start + len;
+        *pos = end;
+
+        Ok(&bytes[start..end])
+    }
+}
+
 impl<T> Read for VecDeque<T>
 where
     T: Read,
@@ -338,6 +493,7 @@ where
     #[inline]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
         let len = usize::read(bytes, pos)?;
+        check_claimed_len(len, bytes, *pos)?;
         let mut vec = VecDeque::with_capacity(len);
         for _ in 0..len {
             let item = T::read(bytes, pos)?;
@@ -391,6 +547,20 @@ impl<K: Read + Write + NumBytes + Ord + Hash, V: Read + Write + NumBytes> Read f
     }
 }
 
+impl<T: Read + Eq + Hash> Read for HashSet<T> {
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let len = usize::read(bytes, pos)?;
+        check_claimed_len(len, bytes, *pos)?;
+        let mut set = HashSet::with_capacity(len);
+        for _ in 0..len {
+            let item = T::read(bytes, pos)?;
+            set.insert(item);
+        }
+        Ok(set)
+    }
+}
+
 impl<T1, T2> Read for (T1, T2)
 where
     T1: Read,
@@ -436,6 +606,46 @@ where
     }
 }
 
+impl<T1, T2, T3, T4, T5> Read for (T1, T2, T3, T4, T5)
+where
+    T1: Read,
+    T2: Read,
+    T3: Read,
+    T4: Read,
+    T5: Read,
+{
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let first = T1::read(bytes, pos)?;
+        let second = T2::read(bytes, pos)?;
+        let third = T3::read(bytes, pos)?;
+        let fourth = T4::read(bytes, pos)?;
+        let fifth = T5::read(bytes, pos)?;
+        Ok((first, second, third, fourth, fifth))
+    }
+}
+
+impl<T1, T2, T3, T4, T5, T6> Read for (T1, T2, T3, T4, T5, T6)
+where
+    T1: Read,
+    T2: Read,
+    T3: Read,
+    T4: Read,
+    T5: Read,
+    T6: Read,
+{
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let first = T1::read(bytes, pos)?;
+        let second = T2::read(bytes, pos)?;
+        let third = T3::read(bytes, pos)?;
+        let fourth = T4::read(bytes, pos)?;
+        let fifth = T5::read(bytes, pos)?;
+        let sixth = T6::read(bytes, pos)?;
+        Ok((first, second, third, fourth, fifth, sixth))
+    }
+}
+
 impl Read for bool {
     #[inline]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
@@ -560,6 +770,21 @@ impl Write for i64 {
     }
 }
 
+impl Write for u128 {
+    #[inline]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        let out = self.to_le_bytes();
+        let start = *pos;
+        let end = start + 16;
+        if bytes.len() < end {
+            return Err(WriteError::NotEnoughSpace);
+        }
+        bytes[start..end].copy_from_slice(&out);
+        *pos = end;
+        Ok(())
+    }
+}
+
 impl Write for f32 {
     #[inline]
     fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
@@ -665,6 +890,23 @@ impl<K: Write + NumBytes, V: Write + NumBytes> Write for HashMap<K, V> {
     }
 }
 
+impl<T: Write + Ord> Write for HashSet<T> {
+    #[inline]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        // HashSet has no defined iteration order, but consensus hashing
+        // needs the same set to always pack to the same bytes, so sort
+        // before writing rather than relying on hash-bucket order.
+        let mut items: Vec<&T> = self.iter().collect();
+        items.sort();
+
+        self.len().write(bytes, pos)?;
+        for item in items {
+            item.write(bytes, pos)?;
+        }
+        Ok(())
+    }
+}
+
 impl<T1: Write, T2: Write> Write for (T1, T2) {
     #[inline]
     fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
@@ -695,6 +937,33 @@ impl<T1: Write, T2: Write, T3: Write, T4: Write> Write for (T1, T2, T3, T4) {
     }
 }
 
+impl<T1: Write, T2: Write, T3: Write, T4: Write, T5: Write> Write for (T1, T2, T3, T4, T5) {
+    #[inline]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.0.write(bytes, pos)?;
+        self.1.write(bytes, pos)?;
+        self.2.write(bytes, pos)?;
+        self.3.write(bytes, pos)?;
+        self.4.write(bytes, pos)?;
+        Ok(())
+    }
+}
+
+impl<T1: Write, T2: Write, T3: Write, T4: Write, T5: Write, T6: Write> Write
+    for (T1, T2, T3, T4, T5, T6)
+{
+    #[inline]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.0.write(bytes, pos)?;
+        self.1.write(bytes, pos)?;
+        self.2.write(bytes, pos)?;
+        self.3.write(bytes, pos)?;
+        self.4.write(bytes, pos)?;
+        self.5.write(bytes, pos)?;
+        Ok(())
+    }
+}
+
 impl<T: Write> Write for Arc<T> {
     #[inline]
     fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
@@ -711,4 +980,95 @@ mod tests {
         assert_eq!("".to_string().num_bytes(), 1);
         assert_eq!("hello".to_string().num_bytes(), 6);
     }
+
+    #[test]
+    fn string_read_rejects_invalid_utf8() {
+        let mut bytes = VarUint32(2).pack().unwrap();
+        bytes.extend_from_slice(&[0xFF, 0xFE]); // not valid UTF-8
+        let mut pos = 0;
+        let err = String::read(&bytes, &mut pos).unwrap_err();
+        assert!(matches!(err, ReadError::ParseError));
+    }
+
+    #[test]
+    fn hash_set_serializes_deterministically_regardless_of_insertion_order() {
+        let a: HashSet<u32> = [3u32, 1, 2].into_iter().collect();
+        let b: HashSet<u32> = [2u32, 3, 1].into_iter().collect();
+
+        assert_eq!(a.pack().unwrap(), b.pack().unwrap());
+
+        let mut pos = 0;
+        let roundtripped = HashSet::<u32>::read(&a.pack().unwrap(), &mut pos).unwrap();
+        assert_eq!(roundtripped, a);
+    }
+
+    #[test]
+    fn u32_packs_little_endian() {
+        // The wire format matches EOSIO: all multi-byte integers are
+        // little-endian. Index keys that need byte-ordered range scans (e.g.
+        // block ids, which embed the block height) deliberately bypass this
+        // and encode big-endian instead, with a comment at the call site.
+        assert_eq!(0x0102_0304u32.pack().unwrap(), vec![0x04, 0x03, 0x02, 0x01]);
+    }
+
+    #[test]
+    fn string_read_rejects_length_longer_than_buffer() {
+        let mut bytes = VarUint32(1_000_000).pack().unwrap();
+        bytes.push(b'a');
+        let mut pos = 0;
+        let err = String::read(&bytes, &mut pos).unwrap_err();
+        assert!(matches!(err, ReadError::NotEnoughBytes));
+    }
+
+    #[test]
+    fn vec_read_rejects_length_prefix_far_exceeding_buffer() {
+        // Claims a billion elements but the buffer has only one byte left.
+        let mut bytes = VarUint32(1_000_000_000).pack().unwrap();
+        bytes.push(0);
+        let mut pos = 0;
+        let err = Vec::<u8>::read(&bytes, &mut pos).unwrap_err();
+        assert!(matches!(err, ReadError::NotEnoughBytes));
+    }
+
+    #[test]
+    fn u128_roundtrips_little_endian() {
+        let value: u128 = (0x0102_0304_0506_0708u128 << 64) | 0x090a_0b0c_0d0e_0f10u128;
+        let packed = value.pack().unwrap();
+        assert_eq!(packed.len(), 16);
+        assert_eq!(packed[0], 0x10);
+        assert_eq!(packed[15], 0x01);
+
+        let mut pos = 0;
+        assert_eq!(u128::read(&packed, &mut pos).unwrap(), value);
+    }
+
+    #[test]
+    fn vec_deque_read_rejects_length_prefix_far_exceeding_buffer() {
+        let mut bytes = VarUint32(1_000_000_000).pack().unwrap();
+        bytes.push(0);
+        let mut pos = 0;
+        let err = VecDeque::<u8>::read(&bytes, &mut pos).unwrap_err();
+        assert!(matches!(err, ReadError::NotEnoughBytes));
+    }
+
+    #[test]
+    fn fixed_size_primitives_report_const_size() {
+        assert_eq!(u8::CONST_SIZE, Some(1));
+        assert_eq!(u32::CONST_SIZE, Some(4));
+        assert_eq!(u64::CONST_SIZE, Some(8));
+        assert_eq!(bool::CONST_SIZE, Some(1));
+    }
+
+    #[test]
+    fn var_size_types_have_no_const_size() {
+        assert_eq!(usize::CONST_SIZE, None);
+        assert_eq!(String::CONST_SIZE, None);
+        assert_eq!(Vec::<u8>::CONST_SIZE, None);
+    }
+
+    #[test]
+    fn tuple_const_size_is_the_sum_of_fixed_fields_but_none_if_any_field_is_variable() {
+        assert_eq!(<(u32, u64)>::CONST_SIZE, Some(12));
+        assert_eq!(<(u32, String)>::CONST_SIZE, None);
+    }
 }
@@ -54,7 +54,7 @@ impl Read for VarUint32 {
         }
 
         // if we fell out of the loop, we saw 5 continuation bits -> too long for u32
-        Err(ReadError::ParseError)
+        Err(ReadError::Overflow)
     }
 }
 
@@ -185,6 +185,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn varuint_read_rejects_six_byte_encoding() {
+        // Five continuation bytes followed by a sixth byte: too long to fit in u32.
+        let mut p = 0;
+        let err = VarUint32::read(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01], &mut p).unwrap_err();
+        assert!(matches!(err, ReadError::Overflow));
+    }
+
+    #[test]
+    fn varuint_read_rejects_value_overflowing_u32() {
+        // The 5th byte carries more than the 4 low bits that fit in a u32.
+        let mut p = 0;
+        let err = VarUint32::read(&[0xFF, 0xFF, 0xFF, 0xFF, 0x10], &mut p).unwrap_err();
+        assert!(matches!(err, ReadError::Overflow));
+    }
+
+    #[test]
+    fn varuint_read_rejects_truncated_encoding() {
+        // A continuation byte with no follow-up byte in the buffer.
+        let mut p = 0;
+        let err = VarUint32::read(&[0x80], &mut p).unwrap_err();
+        assert!(matches!(err, ReadError::NotEnoughBytes));
+    }
+
     #[test]
     fn varuint_write() {
         let mut buf = [0u8; 16];
@@ -0,0 +1,32 @@
+use pulsevm_proc_macros::{NumBytes, Read, Write};
+use pulsevm_serialization::{Read as _, ReadError, Write as _};
+
+// EOSIO `variant`-style enum: a VarUint32 tag (the variant index) followed
+// by the payload, if the variant carries one.
+#[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes)]
+enum Action {
+    Noop,
+    Transfer(u64),
+    Memo(String),
+}
+
+#[test]
+fn round_trips_every_variant() {
+    for action in [
+        Action::Noop,
+        Action::Transfer(42),
+        Action::Memo("hello".to_string()),
+    ] {
+        let packed = action.pack().unwrap();
+        let roundtripped = Action::read(&packed, &mut 0).unwrap();
+        assert_eq!(roundtripped, action);
+    }
+}
+
+#[test]
+fn rejects_out_of_range_tag() {
+    // Tag 3 doesn't exist (only 0, 1, 2 are defined).
+    let bytes = [0x03];
+    let err = Action::read(&bytes, &mut 0).unwrap_err();
+    assert!(matches!(err, ReadError::ParseError));
+}
@@ -6,7 +6,6 @@ mod tests {
     use std::{collections::BTreeSet, fs, path::Path, str::FromStr, sync::Arc, u32, vec};
 
     use pulsevm_core::{
-        ACTIVE_NAME, CODE_NAME, ChainError, Database, OWNER_NAME, PULSE_NAME,
         authority::{Authority, KeyWeight, PermissionLevel, PermissionLevelWeight},
         block::{BlockStatus, BlockTimestamp},
         config::{
@@ -23,6 +22,7 @@ mod tests {
             Action, PackedTransaction, SignedTransaction, Transaction, TransactionTrace,
         },
         utils::pulse_assert,
+        ChainError, Database, ACTIVE_NAME, CODE_NAME, OWNER_NAME, PULSE_NAME,
     };
     use pulsevm_crypto::Bytes;
     use pulsevm_name_macro::name;
@@ -42,6 +42,14 @@ mod tests {
 
     impl Testing {
         pub async fn new() -> Self {
+            Self::new_with_bios_path(None).await
+        }
+
+        /// Same as [`Testing::new`], but loads the bios contract from
+        /// `bios_wasm_path` instead of the embedded default, so tests can
+        /// exercise a locally-built or modified bios contract without it
+        /// living at a fixed location relative to this crate.
+        pub async fn new_with_bios_path(bios_wasm_path: Option<&Path>) -> Self {
             let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
             let chain_id =
                 Id::from_str("c8c4a47932fc0a938972f48f32489e7e91f024697e498ceb3d3c3afcf28f68b6")
@@ -72,7 +80,7 @@ mod tests {
             };
 
             suite
-                .set_bios_contract()
+                .set_bios_contract(bios_wasm_path)
                 .expect("Failed to set bios contract");
 
             suite
@@ -295,15 +303,19 @@ mod tests {
             Ok(())
         }
 
-        pub fn set_bios_contract(&mut self) -> Result<(), ChainError> {
-            let bios_wasm_path = Path::new(env!("CARGO_MANIFEST_DIR"))
-                .parent()
-                .unwrap()
-                .parent()
-                .unwrap()
-                .join("reference_contracts")
-                .join("pulse_bios.wasm");
-            let wasm = fs::read(bios_wasm_path).expect("Failed to read bios wasm file");
+        /// Deploys the bios contract onto [`PULSE_NAME`]. Reads it from
+        /// `bios_wasm_path` if given, otherwise falls back to the copy
+        /// embedded in this binary at compile time, so tests keep working
+        /// regardless of where `reference_contracts/` lives relative to the
+        /// crate that's running them.
+        pub fn set_bios_contract(
+            &mut self,
+            bios_wasm_path: Option<&Path>,
+        ) -> Result<(), ChainError> {
+            let wasm = match bios_wasm_path {
+                Some(path) => fs::read(path).expect("Failed to read bios wasm file"),
+                None => pulsevm_reference_contracts::PULSE_BIOS_WASM.to_vec(),
+            };
             self.set_code(PULSE_NAME, Bytes::from(wasm))?;
             Ok(())
         }
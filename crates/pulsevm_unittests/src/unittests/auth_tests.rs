@@ -652,4 +652,26 @@ mod auth_tests {
         )?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_action_receipt_sequences_track_across_pushed_transactions() -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.create_accounts(vec![name!("alice").into()], false, true)?;
+
+        let mut global_sequences = Vec::new();
+        let mut recv_sequences = Vec::new();
+        for _ in 0..3 {
+            let trace = chain.push_reqauth(name!("alice").into(), "owner", false)?;
+            let receipt = trace.action_traces()[0].receipt.clone().unwrap();
+            global_sequences.push(receipt.global_sequence);
+            recv_sequences.push(receipt.recv_sequence);
+        }
+
+        assert!(global_sequences[0] < global_sequences[1]);
+        assert!(global_sequences[1] < global_sequences[2]);
+        assert!(recv_sequences[0] < recv_sequences[1]);
+        assert!(recv_sequences[1] < recv_sequences[2]);
+
+        Ok(())
+    }
 }
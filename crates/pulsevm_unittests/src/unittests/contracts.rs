@@ -152,3 +152,35 @@ pub static ENTRY_WAST_2: &str = r#"(module
   )
  )
 )"#;
+
+// A contract whose `apply` recurses on itself with no base case, used to
+// exercise the WASM call-depth limit: it must trap cleanly long before the
+// recursion could overflow the node's native stack.
+pub static DEEP_RECURSION_WAST: &str = r#"(module
+ (table 0 funcref)
+ (memory $0 1)
+ (export "memory" (memory $0))
+ (export "apply" (func $apply))
+ (func $apply (param $0 i64) (param $1 i64) (param $2 i64)
+  (call $apply (local.get $0) (local.get $1) (local.get $2))
+ )
+)"#;
+
+// A contract whose `apply` calls `prints_l` in a loop with a 64-byte chunk,
+// 100 times (6400 bytes total), to exercise the console output cap.
+pub static PRINTS_LOOP_WAST: &str = r#"(module
+ (import "env" "prints_l" (func $prints_l (param i32 i32)))
+ (table 0 funcref)
+ (memory $0 1)
+ (export "memory" (memory $0))
+ (export "apply" (func $apply))
+ (data (i32.const 0) "0123456789012345678901234567890123456789012345678901234567890123")
+ (func $apply (param $0 i64) (param $1 i64) (param $2 i64)
+  (local $i i32)
+  (loop $loop
+   (call $prints_l (i32.const 0) (i32.const 64))
+   (local.set $i (i32.add (local.get $i) (i32.const 1)))
+   (br_if $loop (i32.lt_u (local.get $i) (i32.const 100)))
+  )
+ )
+)"#;
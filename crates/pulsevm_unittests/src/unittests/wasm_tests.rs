@@ -3,18 +3,24 @@ mod auth_tests {
     use std::{fs, path::Path, sync::Arc};
 
     use anyhow::Result;
+    use pulsevm_constants::{CONSOLE_TRUNCATION_MARKER, DEFAULT_MAX_CONSOLE_OUTPUT_BYTES};
     use pulsevm_core::{
         authority::PermissionLevel,
+        config::SETCODE_NAME,
+        name::Name,
+        pulse_contract::SetCode,
+        resource_limits::ResourceLimitsManager,
         transaction::{Action, SignedTransaction, Transaction},
-        wat2wasm,
+        wat2wasm, ACTIVE_NAME, PULSE_NAME,
     };
+    use pulsevm_crypto::Bytes;
     use pulsevm_name_macro::name;
 
     use crate::{
-        tests::{Testing, get_private_key},
+        tests::{get_private_key, Testing},
         unittests::contracts::{
-            ALIGNED_CONST_REF_WAST, ALIGNED_REF_WAST, ENTRY_WAST, ENTRY_WAST_2,
-            MISALIGNED_CONST_REF_WAST, MISALIGNED_REF_WAST,
+            ALIGNED_CONST_REF_WAST, ALIGNED_REF_WAST, DEEP_RECURSION_WAST, ENTRY_WAST,
+            ENTRY_WAST_2, MISALIGNED_CONST_REF_WAST, MISALIGNED_REF_WAST, PRINTS_LOOP_WAST,
         },
     };
 
@@ -104,6 +110,211 @@ mod auth_tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_setcode_updates_the_account_code_hash() -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.create_accounts(vec![name!("codehash").into()], false, true)?;
+        chain.set_code(name!("codehash").into(), wat2wasm(ENTRY_WAST)?.into())?;
+
+        let db = chain.controller.database();
+        let first_hash = db
+            .get_account_metadata(name!("codehash"))?
+            .get_code_hash()
+            .to_string();
+
+        chain.set_code(name!("codehash").into(), wat2wasm(ENTRY_WAST_2)?.into())?;
+
+        let second_hash = db
+            .get_account_metadata(name!("codehash"))?
+            .get_code_hash()
+            .to_string();
+
+        assert_ne!(first_hash, second_hash);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_setcode_rejects_redeploying_the_identical_code() -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.create_accounts(vec![name!("samecode").into()], false, true)?;
+        let wasm = wat2wasm(ENTRY_WAST)?;
+        chain.set_code(name!("samecode").into(), wasm.clone().into())?;
+
+        assert!(chain
+            .set_code(name!("samecode").into(), wasm.into())
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_setcode_clearing_code_refunds_ram() -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.create_accounts(vec![name!("clearcode").into()], false, true)?;
+        chain.set_code(name!("clearcode").into(), wat2wasm(ENTRY_WAST)?.into())?;
+
+        let clearcode: Name = name!("clearcode").into();
+        let ram_with_code =
+            ResourceLimitsManager::get_account_ram_usage(&chain.controller.database(), &clearcode)?;
+
+        chain.set_code(clearcode, vec![].into())?;
+
+        let ram_without_code =
+            ResourceLimitsManager::get_account_ram_usage(&chain.controller.database(), &clearcode)?;
+        assert!(ram_without_code < ram_with_code);
+        assert!(chain
+            .controller
+            .database()
+            .get_account_metadata(clearcode.as_u64())?
+            .get_code_hash()
+            .empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pushing_transactions_registers_non_zero_cpu_and_net_usage() -> Result<()> {
+        let mut chain = Testing::new().await;
+
+        // `pulse` is the creator/payer for every `create_account` below, so
+        // its resource usage accumulators are the ones that move. The core
+        // contract here has no token-transfer action, so a second
+        // lightweight action (`reqauth`) stands in for "do something else
+        // that consumes resources" rather than a transfer.
+        chain.create_accounts(vec![name!("alice").into()], false, true)?;
+        chain.push_reqauth(PULSE_NAME, "active", false)?;
+
+        let (net_used, _net_available, _net_max) = ResourceLimitsManager::get_account_net_usage(
+            &chain.controller.database(),
+            &PULSE_NAME,
+            None,
+        )?;
+        let (cpu_used, _cpu_available, _cpu_max) = ResourceLimitsManager::get_account_cpu_usage(
+            &chain.controller.database(),
+            &PULSE_NAME,
+            None,
+        )?;
+
+        assert!(net_used > 0);
+        assert!(cpu_used > 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_trace_account_ram_delta_tracks_storing_and_removing_code() -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.create_accounts(vec![name!("ramdelta").into()], false, true)?;
+        let ramdelta: Name = name!("ramdelta").into();
+
+        let setcode = |chain: &mut Testing, wasm: Vec<u8>| -> Result<i64> {
+            let mut trx = Transaction::default();
+            chain.set_transaction_headers(&mut trx, u32::MAX, 0);
+            trx.actions.push(Action::new(
+                PULSE_NAME.into(),
+                SETCODE_NAME.into(),
+                SetCode {
+                    account: ramdelta,
+                    vm_type: 0,
+                    vm_version: 0,
+                    code: Arc::new(Bytes::from(wasm)),
+                }
+                .pack()?,
+                vec![PermissionLevel::new(
+                    ramdelta.as_u64(),
+                    ACTIVE_NAME.as_u64(),
+                )],
+            ));
+            let trx = trx.sign(
+                &get_private_key(ramdelta, "active"),
+                chain.controller.chain_id(),
+            )?;
+            let trace = chain.push_transaction(trx)?;
+            let delta = trace
+                .account_ram_delta
+                .expect("setcode should produce a ram delta on the trace");
+            assert_eq!(delta.account, ramdelta);
+            Ok(delta.delta)
+        };
+
+        let store_delta = setcode(&mut chain, wat2wasm(ENTRY_WAST)?)?;
+        assert!(store_delta > 0);
+
+        let remove_delta = setcode(&mut chain, vec![])?;
+        assert!(remove_delta < 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_console_output_is_truncated_at_the_configured_cap() -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.create_accounts(vec![name!("printloop").into()], false, true)?;
+        chain.set_code(
+            name!("printloop").into(),
+            wat2wasm(PRINTS_LOOP_WAST)?.into(),
+        )?;
+
+        let mut trx = Transaction::default();
+        chain.set_transaction_headers(&mut trx, u32::MAX, 0);
+        trx.actions.push(Action {
+            account: name!("printloop").into(),
+            name: name!("").into(),
+            authorization: vec![PermissionLevel {
+                actor: name!("printloop").into(),
+                permission: name!("active").into(),
+            }],
+            data: Arc::from(vec![]),
+        });
+        let trx = trx.sign(
+            &get_private_key(name!("printloop").into(), "active"),
+            chain.controller.chain_id(),
+        )?;
+        let trace = chain.push_transaction(trx)?;
+
+        let console = &trace.action_traces()[0].console;
+        assert!(console.ends_with(CONSOLE_TRUNCATION_MARKER));
+        assert_eq!(
+            console.len(),
+            DEFAULT_MAX_CONSOLE_OUTPUT_BYTES + CONSOLE_TRUNCATION_MARKER.len()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_embedded_bios_wasm_loads_without_touching_the_filesystem() -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.set_code(
+            name!("pulse").into(),
+            pulsevm_reference_contracts::PULSE_BIOS_WASM.into(),
+        )?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_new_with_default_bios_path_can_create_accounts() -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.create_accounts(vec![name!("defaultbios").into()], false, false)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_new_with_explicit_bios_path_can_create_accounts() -> Result<()> {
+        let bios_wasm_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("reference_contracts")
+            .join("pulse_bios.wasm");
+
+        let mut chain = Testing::new_with_bios_path(Some(&bios_wasm_path)).await;
+        chain.create_accounts(vec![name!("explicitbios").into()], false, false)?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_endless_loop() -> Result<()> {
         let mut chain = Testing::new().await;
@@ -137,4 +348,34 @@ mod auth_tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_deep_recursion_fails_cleanly_instead_of_overflowing_the_native_stack(
+    ) -> Result<()> {
+        let mut chain = Testing::new().await;
+        chain.create_accounts(vec![name!("recurse").into()], false, true)?;
+        chain.set_code(
+            name!("recurse").into(),
+            wat2wasm(DEEP_RECURSION_WAST)?.into(),
+        )?;
+
+        let mut trx = Transaction::default();
+        chain.set_transaction_headers(&mut trx, u32::MAX, 0);
+        trx.actions.push(Action {
+            account: name!("recurse").into(),
+            name: name!("").into(),
+            authorization: vec![PermissionLevel {
+                actor: name!("recurse").into(),
+                permission: name!("active").into(),
+            }],
+            data: Arc::from(vec![]),
+        });
+        let trx = trx.sign(
+            &get_private_key(name!("recurse").into(), "active"),
+            chain.controller.chain_id(),
+        )?;
+        assert!(chain.push_transaction(trx).is_err());
+
+        Ok(())
+    }
 }